@@ -0,0 +1,352 @@
+use chrono::{DateTime, Local, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::reports::{entry_local_date, round_to, RoundingRule};
+use crate::{entry_elapsed_seconds, fetch_time_entries_range, parse_timestamp, TimeEntry, TimeEntryFilter};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    // Grouped client → project → day with subtotals, for building invoices
+    Invoice,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json | ExportFormat::Invoice => "json",
+        }
+    }
+}
+
+// One entry flattened for export; times are local with their UTC offset
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportRow {
+    pub id: i32,
+    pub date: String,
+    pub started_at: String,
+    pub stopped_at: Option<String>,
+    pub duration_minutes: f64,
+    pub duration_hours: f64,
+    pub client: Option<String>,
+    pub project: String,
+    pub description: String,
+    pub is_billable: bool,
+    pub hourly_rate: Option<f64>,
+    pub amount: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct InvoiceTotals {
+    pub hours: f64,
+    pub billable_hours: f64,
+    pub amount: Option<f64>,
+}
+
+impl InvoiceTotals {
+    fn add(&mut self, row: &ExportRow) {
+        self.hours += row.duration_hours;
+        if row.is_billable {
+            self.billable_hours += row.duration_hours;
+        }
+        if let Some(amount) = row.amount {
+            *self.amount.get_or_insert(0.0) += amount;
+        }
+    }
+
+    fn rounded(mut self) -> Self {
+        self.hours = round_to(self.hours, 2);
+        self.billable_hours = round_to(self.billable_hours, 2);
+        self.amount = self.amount.map(|a| round_to(a, 2));
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InvoiceDay {
+    pub date: String,
+    pub descriptions: Vec<String>,
+    pub totals: InvoiceTotals,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InvoiceProject {
+    pub project: String,
+    pub days: Vec<InvoiceDay>,
+    pub totals: InvoiceTotals,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InvoiceClient {
+    pub client: String,
+    pub hourly_rate: Option<f64>,
+    pub projects: Vec<InvoiceProject>,
+    pub totals: InvoiceTotals,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct InvoiceExport {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub rounding: RoundingRule,
+    pub clients: Vec<InvoiceClient>,
+    pub totals: InvoiceTotals,
+}
+
+const CSV_HEADER: [&str; 12] = [
+    "id", "date", "started_at", "stopped_at", "duration_minutes", "duration_hours",
+    "client", "project", "description", "billable", "hourly_rate", "amount",
+];
+
+// RFC 3339 in the given timezone, with its offset (e.g. 2024-03-01T09:30:00-05:00)
+pub fn format_local<Tz: TimeZone>(dt: DateTime<Utc>, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    dt.with_timezone(tz).to_rfc3339_opts(SecondsFormat::Secs, false)
+}
+
+// Quote a CSV field when it contains a delimiter, quote or line break (RFC 4180)
+pub fn escape_csv(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Flatten entries (oldest first), applying the rounding rule to each duration
+pub fn export_rows(entries: &[TimeEntry], rounding: RoundingRule, now: DateTime<Utc>) -> Vec<ExportRow> {
+    let mut rows: Vec<(DateTime<Utc>, ExportRow)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let started_at = parse_timestamp(&entry.started_at)?;
+            let minutes = rounding.apply(entry_elapsed_seconds(entry, now) as f64 / 60.0);
+            let client = entry.project.as_ref().and_then(|p| p.client.as_ref());
+            let hourly_rate = client.and_then(|c| c.hourly_rate);
+            let hours = minutes / 60.0;
+
+            Some((started_at, ExportRow {
+                id: entry.id,
+                date: entry_local_date(entry)?.to_string(),
+                started_at: format_local(started_at, &Local),
+                stopped_at: entry
+                    .stopped_at
+                    .as_deref()
+                    .and_then(parse_timestamp)
+                    .map(|dt| format_local(dt, &Local)),
+                duration_minutes: round_to(minutes, 2),
+                duration_hours: round_to(hours, 2),
+                client: client.map(|c| c.name.clone()),
+                // The bare name; the client has its own column
+                project: entry.project.as_ref().map(|p| p.name.clone()).unwrap_or_default(),
+                description: entry.description.clone().unwrap_or_default(),
+                is_billable: entry.is_billable,
+                hourly_rate,
+                amount: hourly_rate
+                    .filter(|_| entry.is_billable)
+                    .map(|rate| round_to(hours * rate, 2)),
+            }))
+        })
+        .collect();
+
+    // Local strings with different offsets (e.g. across DST) don't sort chronologically
+    rows.sort_by_key(|(started_at, _)| *started_at);
+    rows.into_iter().map(|(_, row)| row).collect()
+}
+
+pub fn to_csv(rows: &[ExportRow]) -> String {
+    let mut out = CSV_HEADER.join(",");
+    out.push_str("\r\n");
+
+    for row in rows {
+        let fields = [
+            row.id.to_string(),
+            row.date.clone(),
+            row.started_at.clone(),
+            row.stopped_at.clone().unwrap_or_default(),
+            row.duration_minutes.to_string(),
+            format!("{:.2}", row.duration_hours),
+            row.client.clone().unwrap_or_default(),
+            row.project.clone(),
+            row.description.clone(),
+            if row.is_billable { "yes" } else { "no" }.to_string(),
+            row.hourly_rate.map(|r| r.to_string()).unwrap_or_default(),
+            row.amount.map(|a| format!("{:.2}", a)).unwrap_or_default(),
+        ];
+        let escaped: Vec<String> = fields.iter().map(|f| escape_csv(f)).collect();
+        out.push_str(&escaped.join(","));
+        out.push_str("\r\n");
+    }
+
+    out
+}
+
+// Group rows client → project → day, keeping first-seen order within each level
+pub fn to_invoice(rows: &[ExportRow], from: Option<String>, to: Option<String>, rounding: RoundingRule) -> InvoiceExport {
+    let mut clients: Vec<InvoiceClient> = Vec::new();
+    let mut totals = InvoiceTotals::default();
+
+    for row in rows {
+        let client_name = row.client.clone().unwrap_or_else(|| "No client".to_string());
+        let client = match clients.iter().position(|c| c.client == client_name) {
+            Some(i) => &mut clients[i],
+            None => {
+                clients.push(InvoiceClient {
+                    client: client_name,
+                    hourly_rate: row.hourly_rate,
+                    projects: Vec::new(),
+                    totals: InvoiceTotals::default(),
+                });
+                clients.last_mut().unwrap()
+            }
+        };
+
+        let project = match client.projects.iter().position(|p| p.project == row.project) {
+            Some(i) => &mut client.projects[i],
+            None => {
+                client.projects.push(InvoiceProject {
+                    project: row.project.clone(),
+                    days: Vec::new(),
+                    totals: InvoiceTotals::default(),
+                });
+                client.projects.last_mut().unwrap()
+            }
+        };
+
+        let day = match project.days.iter().position(|d| d.date == row.date) {
+            Some(i) => &mut project.days[i],
+            None => {
+                project.days.push(InvoiceDay {
+                    date: row.date.clone(),
+                    descriptions: Vec::new(),
+                    totals: InvoiceTotals::default(),
+                });
+                project.days.last_mut().unwrap()
+            }
+        };
+
+        if !row.description.is_empty() && !day.descriptions.contains(&row.description) {
+            day.descriptions.push(row.description.clone());
+        }
+        day.totals.add(row);
+        project.totals.add(row);
+        client.totals.add(row);
+        totals.add(row);
+    }
+
+    for client in &mut clients {
+        client.totals = std::mem::take(&mut client.totals).rounded();
+        for project in &mut client.projects {
+            project.totals = std::mem::take(&mut project.totals).rounded();
+            for day in &mut project.days {
+                day.totals = std::mem::take(&mut day.totals).rounded();
+            }
+        }
+    }
+
+    InvoiceExport {
+        from,
+        to,
+        rounding,
+        clients,
+        totals: totals.rounded(),
+    }
+}
+
+// Entries matching the filter rendered as CSV, JSON or an invoice summary
+pub async fn render_with_rounding(filter: &TimeEntryFilter, format: ExportFormat, rounding: RoundingRule) -> Result<String, String> {
+    let entries = fetch_time_entries_range(filter).await?;
+    let rows = export_rows(&entries, rounding, Utc::now());
+
+    match format {
+        ExportFormat::Csv => Ok(to_csv(&rows)),
+        ExportFormat::Json => serde_json::to_string_pretty(&rows).map_err(|e| e.to_string()),
+        ExportFormat::Invoice => {
+            let invoice = to_invoice(&rows, filter.from.clone(), filter.to.clone(), rounding);
+            serde_json::to_string_pretty(&invoice).map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format_timestamp, Client, Project};
+    use chrono::{Duration, FixedOffset};
+
+    fn at(value: &str) -> DateTime<Utc> {
+        parse_timestamp(value).unwrap()
+    }
+
+    fn entry(id: i32, started_at: &str, minutes: f64, description: &str) -> TimeEntry {
+        let started = at(started_at);
+        TimeEntry {
+            id,
+            project_id: 1,
+            project: Some(Project {
+                id: 1,
+                name: "Website".to_string(),
+                client: Some(Client { id: 2, name: "Acme".to_string(), hourly_rate: Some(120.0) }),
+            }),
+            description: Some(description.to_string()),
+            started_at: format_timestamp(started),
+            stopped_at: Some(format_timestamp(started + Duration::seconds((minutes * 60.0) as i64))),
+            resumed_at: None,
+            duration_minutes: Some(minutes),
+            is_billable: true,
+        }
+    }
+
+    #[test]
+    fn escape_csv_quotes_only_when_needed() {
+        assert_eq!(escape_csv("plain text"), "plain text");
+        assert_eq!(escape_csv("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv("two\nlines"), "\"two\nlines\"");
+        assert_eq!(escape_csv("cr\r"), "\"cr\r\"");
+    }
+
+    #[test]
+    fn format_local_keeps_offset() {
+        let dt = at("2024-03-01T14:30:00Z");
+        let est = FixedOffset::west_opt(5 * 3600).unwrap();
+        assert_eq!(format_local(dt, &est), "2024-03-01T09:30:00-05:00");
+        assert_eq!(format_local(dt, &Utc), "2024-03-01T14:30:00+00:00");
+    }
+
+    #[test]
+    fn rows_sorted_by_utc_start() {
+        let entries = vec![
+            entry(2, "2024-03-01T15:00:00Z", 30.0, "later"),
+            entry(1, "2024-03-01T09:00:00Z", 30.0, "earlier"),
+        ];
+        let ids: Vec<i32> = export_rows(&entries, RoundingRule::default(), Utc::now()).iter().map(|r| r.id).collect();
+        assert_eq!(ids, vec![1, 2]);
+    }
+
+    #[test]
+    fn csv_has_header_and_escaped_fields() {
+        let entries = vec![entry(7, "2024-03-01T09:00:00Z", 45.0, "Fix \"login\", again")];
+        let rows = export_rows(&entries, RoundingRule::default(), Utc::now());
+        let csv = to_csv(&rows);
+        let lines: Vec<&str> = csv.split("\r\n").collect();
+
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2], "");
+        let row = &rows[0];
+        assert_eq!(
+            lines[1],
+            format!(
+                "7,{},{},{},45,0.75,Acme,Website,\"Fix \"\"login\"\", again\",yes,120,90.00",
+                row.date,
+                row.started_at,
+                row.stopped_at.as_deref().unwrap()
+            )
+        );
+    }
+}
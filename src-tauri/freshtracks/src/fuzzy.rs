@@ -0,0 +1,115 @@
+// Small fuzzy matcher shared by description suggestions and project search
+
+// Lowercased alphanumeric words of `text`
+pub fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect()
+}
+
+// Whether the characters of `needle` appear in order in `haystack`
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut chars = haystack.chars();
+    needle.chars().all(|n| chars.any(|h| h == n))
+}
+
+// Score how well `query` matches `text` (higher is better, None for no match).
+// Every query word must match some word of the text: a word prefix scores best,
+// then a substring, then the letters in order. Matches in order and a match on
+// the first word earn a small bonus. An empty query matches everything with 0.
+pub fn score(query: &str, text: &str) -> Option<f64> {
+    let query_words = words(query);
+    if query_words.is_empty() {
+        return Some(0.0);
+    }
+    let text_words = words(text);
+    if text_words.is_empty() {
+        return None;
+    }
+
+    let mut total = 0.0;
+    let mut last_index: Option<usize> = None;
+    let mut in_order = true;
+
+    for (i, query_word) in query_words.iter().enumerate() {
+        let mut best: Option<(f64, usize)> = None;
+        for (index, word) in text_words.iter().enumerate() {
+            let quality = if word.starts_with(query_word.as_str()) {
+                1.0
+            } else if word.contains(query_word.as_str()) {
+                0.6
+            } else if is_subsequence(query_word, word) {
+                0.3
+            } else {
+                continue;
+            };
+            // Prefer the earliest word after the previous match on ties
+            let after_previous = last_index.map_or(true, |last| index > last);
+            let quality = if after_previous { quality } else { quality - 0.05 };
+            if best.map_or(true, |(q, _)| quality > q) {
+                best = Some((quality, index));
+            }
+        }
+
+        let (quality, index) = best?;
+        if i == 0 && index == 0 {
+            total += 0.25;
+        }
+        if last_index.is_some_and(|last| index <= last) {
+            in_order = false;
+        }
+        last_index = Some(index);
+        total += quality;
+    }
+
+    if in_order {
+        total += 0.25;
+    }
+    Some(total / query_words.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_score(query: &str, text: &str, expected: f64) {
+        let actual = score(query, text).unwrap();
+        assert!((actual - expected).abs() < 1e-9, "{} vs {}: {} != {}", query, text, actual, expected);
+    }
+
+    #[test]
+    fn words_split_on_punctuation() {
+        assert_eq!(words("Fix the API-client, v2!"), vec!["fix", "the", "api", "client", "v2"]);
+        assert!(words(" -- ").is_empty());
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(score("", "anything"), Some(0.0));
+        assert_eq!(score("  ", ""), Some(0.0));
+        assert_eq!(score("fix", ""), None);
+    }
+
+    #[test]
+    fn every_query_word_must_match() {
+        assert_eq!(score("xyz", "fix login"), None);
+        assert_eq!(score("fix zzz", "fix login"), None);
+    }
+
+    #[test]
+    fn prefix_beats_substring_beats_subsequence() {
+        assert_score("log", "login page", 1.5);
+        assert_score("gin", "login page", 1.1);
+        assert_score("lgn", "login page", 0.8);
+        assert_eq!(score("LOG", "Login Page"), score("log", "login page"));
+    }
+
+    #[test]
+    fn first_word_and_order_bonuses() {
+        // Matching a later word misses the first-word bonus
+        assert_score("page", "login page", 1.25);
+        assert_score("fix login", "fix login bug", 1.25);
+        assert_score("login fix", "fix login bug", 0.975);
+    }
+}
@@ -0,0 +1,739 @@
+// FreshTracks widget API client: the types, requests and time helpers shared by the
+// menu bar app and the headless `freshtracks` CLI. Nothing here depends on tauri.
+
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+pub mod export;
+pub mod fuzzy;
+pub mod reports;
+pub mod search;
+
+// API types
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Client {
+    #[serde(default)]
+    pub id: i32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default, deserialize_with = "deserialize_optional_f64")]
+    pub hourly_rate: Option<f64>,
+}
+
+// Helper to deserialize hourly_rate which might be string, number, or null
+fn deserialize_optional_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let value: Option<serde_json::Value> = Option::deserialize(deserializer)?;
+    match value {
+        None => Ok(None),
+        Some(serde_json::Value::Null) => Ok(None),
+        Some(serde_json::Value::Number(n)) => Ok(n.as_f64()),
+        Some(serde_json::Value::String(s)) => {
+            let trimmed = s.trim();
+            if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("null") {
+                Ok(None)
+            } else {
+                // Be lenient - if it's not a valid number, just return None
+                Ok(trimmed.parse::<f64>().ok())
+            }
+        }
+        Some(_) => Ok(None),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct UserSettings {
+    pub show_live_revenue: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Project {
+    pub id: i32,
+    pub name: String,
+    pub client: Option<Client>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimeEntry {
+    pub id: i32,
+    pub project_id: i32,
+    pub project: Option<Project>,
+    pub description: Option<String>,
+    pub started_at: String,
+    pub stopped_at: Option<String>,
+    pub resumed_at: Option<String>,
+    pub duration_minutes: Option<f64>,
+    pub is_billable: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiResponse<T> {
+    pub data: T,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdateEntryData {
+    pub project_id: i32,
+    pub description: Option<String>,
+    pub started_at: String,
+    pub stopped_at: Option<String>,
+    pub is_billable: bool,
+    // Start of the current session of a resumed entry; left unchanged when None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resumed_at: Option<String>,
+}
+
+const API_BASE: &str = "http://localhost:8000/api/widget";
+const WIDGET_SECRET: &str = "freshtracks-widget-dev-secret";
+
+// One client shared by all requests so connections are pooled
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(reqwest::Client::new);
+
+fn get_http_client() -> reqwest::Client {
+    HTTP_CLIENT.clone()
+}
+
+// Format elapsed seconds as MM:SS or HH:MM:SS
+pub fn format_timer_text(elapsed_seconds: i64) -> String {
+    let hours = elapsed_seconds / 3600;
+    let minutes = (elapsed_seconds % 3600) / 60;
+    let seconds = elapsed_seconds % 60;
+
+    if hours > 0 {
+        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+// Display name for an entry's project, prefixed with the client when known
+pub fn project_label(project: Option<&Project>) -> String {
+    project
+        .map(|p| {
+            if let Some(client) = &p.client {
+                format!("{} - {}", client.name, p.name)
+            } else {
+                p.name.clone()
+            }
+        })
+        .unwrap_or_else(|| "Project".to_string())
+}
+
+// Parse an API timestamp (ISO 8601 / RFC 3339) into UTC
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok()
+}
+
+// Format a timestamp the way the API expects (UTC ISO 8601, like JS toISOString)
+pub fn format_timestamp(dt: DateTime<Utc>) -> String {
+    dt.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+// Elapsed seconds for an entry: accumulated duration plus the current session if running.
+// On a running entry, duration_minutes is the time banked by earlier sessions (pauses),
+// so it only counts once the entry has been resumed.
+pub fn entry_elapsed_seconds(entry: &TimeEntry, now: DateTime<Utc>) -> i64 {
+    let accumulated = (entry.duration_minutes.unwrap_or(0.0) * 60.0).round() as i64;
+    if entry.stopped_at.is_some() {
+        return accumulated;
+    }
+    let accumulated = if entry.resumed_at.is_some() { accumulated } else { 0 };
+
+    let session_start = parse_timestamp(entry.resumed_at.as_deref().unwrap_or(&entry.started_at));
+
+    match session_start {
+        Some(start) => accumulated + (now - start).num_seconds().max(0),
+        None => accumulated,
+    }
+}
+
+// Most recently started entry that has been stopped (the default restart target)
+pub fn last_stopped_entry(entries: &[TimeEntry]) -> Option<TimeEntry> {
+    entries
+        .iter()
+        .filter(|e| e.stopped_at.is_some())
+        .max_by_key(|e| parse_timestamp(&e.started_at))
+        .cloned()
+}
+
+// API requests
+pub async fn start_timer(project_id: i32, description: Option<String>) -> Result<TimeEntry, String> {
+    let client = get_http_client();
+
+    #[derive(Serialize)]
+    struct StartTimerRequest {
+        project_id: i32,
+        description: Option<String>,
+    }
+
+    let body = StartTimerRequest {
+        project_id,
+        description,
+    };
+
+    let response = client
+        .post(format!("{}/time-entries", API_BASE))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let timer = response
+        .json::<TimeEntry>()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(timer)
+}
+
+// Create an entry with explicit times; finished when stopped_at is set
+pub async fn create_entry(
+    project_id: i32,
+    description: Option<String>,
+    started_at: String,
+    stopped_at: Option<String>,
+    is_billable: bool,
+) -> Result<TimeEntry, String> {
+    let client = get_http_client();
+
+    #[derive(Serialize)]
+    struct CreateEntryRequest {
+        project_id: i32,
+        description: Option<String>,
+        started_at: String,
+        stopped_at: Option<String>,
+        is_billable: bool,
+    }
+
+    let body = CreateEntryRequest {
+        project_id,
+        description,
+        started_at,
+        stopped_at,
+        is_billable,
+    };
+
+    let response = client
+        .post(format!("{}/time-entries", API_BASE))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .json(&body)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    response
+        .json::<TimeEntry>()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+pub async fn stop_timer(timer_id: i32) -> Result<TimeEntry, String> {
+    let client = get_http_client();
+
+    let response = client
+        .post(format!("{}/time-entries/{}/stop", API_BASE, timer_id))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let timer = response
+        .json::<TimeEntry>()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(timer)
+}
+
+pub async fn get_active_timer() -> Result<Option<TimeEntry>, String> {
+    let client = get_http_client();
+    let response = client
+        .get(format!("{}/active-timer", API_BASE))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 404 || response.status() == 204 {
+        return Ok(None);
+    }
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+
+    // Laravel returns null as JSON when no active timer
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    if text == "null" || text.is_empty() {
+        return Ok(None);
+    }
+
+    let timer: TimeEntry = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    Ok(Some(timer))
+}
+
+pub async fn get_time_entries() -> Result<Vec<TimeEntry>, String> {
+    let client = get_http_client();
+
+    let response = client
+        .get(format!("{}/time-entries", API_BASE))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+
+    let entries: Vec<TimeEntry> = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(entries)
+}
+
+// Filters for range queries; dates are local "YYYY-MM-DD", both ends inclusive
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TimeEntryFilter {
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub client_id: Option<i32>,
+    pub project_id: Option<i32>,
+    pub is_billable: Option<bool>,
+}
+
+// Pagination details of a Laravel paginated response (plain paginator or API resource)
+#[derive(Debug, Deserialize, Default)]
+struct PaginationMeta {
+    current_page: Option<u32>,
+    last_page: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct PaginationLinks {
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PaginatedEntries {
+    data: Vec<TimeEntry>,
+    #[serde(flatten)]
+    page: PaginationMeta,
+    next_page_url: Option<String>,
+    #[serde(default)]
+    meta: Option<PaginationMeta>,
+    #[serde(default)]
+    links: Option<PaginationLinks>,
+}
+
+impl PaginatedEntries {
+    fn has_more(&self) -> bool {
+        if self.next_page_url.is_some() || self.links.as_ref().is_some_and(|l| l.next.is_some()) {
+            return true;
+        }
+        let page = self.meta.as_ref().unwrap_or(&self.page);
+        matches!((page.current_page, page.last_page), (Some(current), Some(last)) if current < last)
+    }
+}
+
+// /time-entries returns a bare array unless the server paginates
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum TimeEntriesPage {
+    Plain(Vec<TimeEntry>),
+    Paginated(PaginatedEntries),
+}
+
+// Upper bound on pages fetched for one query
+const MAX_ENTRY_PAGES: u32 = 50;
+const ENTRIES_PER_PAGE: u32 = 100;
+
+// UTC instant of local midnight at the start of `date`
+fn local_day_start(date: chrono::NaiveDate) -> DateTime<Utc> {
+    date.and_hms_opt(0, 0, 0)
+        .and_then(|dt| dt.and_local_timezone(Local).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+        .unwrap_or_else(|| date.and_hms_opt(0, 0, 0).unwrap().and_utc())
+}
+
+fn entry_matches_filter(entry: &TimeEntry, filter: &TimeEntryFilter, from: Option<chrono::NaiveDate>, to: Option<chrono::NaiveDate>) -> bool {
+    let date = reports::entry_local_date(entry);
+    if from.is_some_and(|from| date.map_or(true, |d| d < from)) || to.is_some_and(|to| date.map_or(true, |d| d > to)) {
+        return false;
+    }
+    if filter.project_id.is_some_and(|id| entry.project_id != id) {
+        return false;
+    }
+    if let Some(client_id) = filter.client_id {
+        let entry_client = entry.project.as_ref().and_then(|p| p.client.as_ref()).map(|c| c.id);
+        if entry_client != Some(client_id) {
+            return false;
+        }
+    }
+    filter.is_billable.map_or(true, |billable| entry.is_billable == billable)
+}
+
+// Fetch entries matching the filter, following Laravel pagination. Filters are sent
+// to the server and re-applied locally in case it ignores some of them.
+pub async fn fetch_time_entries_range(filter: &TimeEntryFilter) -> Result<Vec<TimeEntry>, String> {
+    let from = filter.from.as_deref().map(reports::parse_date).transpose()?;
+    let to = filter.to.as_deref().map(reports::parse_date).transpose()?;
+    if let (Some(from), Some(to)) = (from, to) {
+        if to < from {
+            return Err("The end date is before the start date".to_string());
+        }
+    }
+
+    let mut query: Vec<(&str, String)> = vec![("per_page", ENTRIES_PER_PAGE.to_string())];
+    if let Some(from) = from {
+        query.push(("from", format_timestamp(local_day_start(from))));
+    }
+    if let Some(to) = to {
+        query.push(("to", format_timestamp(local_day_start(to + chrono::Duration::days(1)))));
+    }
+    if let Some(client_id) = filter.client_id {
+        query.push(("client_id", client_id.to_string()));
+    }
+    if let Some(project_id) = filter.project_id {
+        query.push(("project_id", project_id.to_string()));
+    }
+    if let Some(billable) = filter.is_billable {
+        query.push(("is_billable", if billable { "1" } else { "0" }.to_string()));
+    }
+
+    let client = get_http_client();
+    let mut entries: Vec<TimeEntry> = Vec::new();
+
+    for page in 1..=MAX_ENTRY_PAGES {
+        let response = client
+            .get(format!("{}/time-entries", API_BASE))
+            .header("X-Widget-Secret", WIDGET_SECRET)
+            .query(&query)
+            .query(&[("page", page)])
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status() == 401 {
+            return Err("Widget authentication failed. Check your secret.".to_string());
+        }
+
+        if !response.status().is_success() {
+            return Err(format!("API error: {}", response.status()));
+        }
+
+        let body: TimeEntriesPage = response
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match body {
+            TimeEntriesPage::Plain(page_entries) => {
+                entries.extend(page_entries);
+                break;
+            }
+            TimeEntriesPage::Paginated(paginated) => {
+                let has_more = paginated.has_more() && !paginated.data.is_empty();
+                entries.extend(paginated.data);
+                if !has_more {
+                    break;
+                }
+                if page == MAX_ENTRY_PAGES {
+                    eprintln!("Stopped fetching time entries after {} pages", MAX_ENTRY_PAGES);
+                }
+            }
+        }
+    }
+
+    // Pages can overlap if entries change while paging
+    let mut seen = std::collections::HashSet::new();
+    entries.retain(|e| seen.insert(e.id));
+    entries.retain(|e| entry_matches_filter(e, filter, from, to));
+    entries.sort_by_key(|e| std::cmp::Reverse(parse_timestamp(&e.started_at)));
+
+    Ok(entries)
+}
+
+pub async fn restart_timer(timer_id: i32) -> Result<TimeEntry, String> {
+    let client = get_http_client();
+
+    let response = client
+        .post(format!("{}/time-entries/{}/restart", API_BASE, timer_id))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let timer = response
+        .json::<TimeEntry>()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(timer)
+}
+
+// Delete an entry (the app records undo snapshots around this)
+pub async fn delete_entry(entry_id: i32) -> Result<(), String> {
+    let client = get_http_client();
+
+    let response = client
+        .delete(format!("{}/time-entries/{}", API_BASE, entry_id))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    Ok(())
+}
+
+// Update an entry (the app records undo snapshots around this)
+pub async fn update_entry(entry_id: i32, data: UpdateEntryData) -> Result<TimeEntry, String> {
+    let client = get_http_client();
+
+    let response = client
+        .put(format!("{}/time-entries/{}", API_BASE, entry_id))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .json(&data)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("API error {}: {}", status, body));
+    }
+
+    let entry = response
+        .json::<TimeEntry>()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(entry)
+}
+
+pub async fn get_recent_projects() -> Result<Vec<Project>, String> {
+    let client = get_http_client();
+
+    let response = client
+        .get(format!("{}/projects", API_BASE))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+
+    let projects: Vec<Project> = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(projects)
+}
+
+pub async fn get_clients() -> Result<Vec<Client>, String> {
+    let client = get_http_client();
+
+    let response = client
+        .get(format!("{}/clients", API_BASE))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+
+    let clients: Vec<Client> = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(clients)
+}
+
+pub async fn get_client_projects(client_id: i32) -> Result<Vec<Project>, String> {
+    let client = get_http_client();
+
+    let response = client
+        .get(format!("{}/clients/{}/projects", API_BASE, client_id))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        return Err(format!("API error: {}", response.status()));
+    }
+
+    let projects: Vec<Project> = response
+        .json()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(projects)
+}
+
+pub async fn get_settings() -> Result<UserSettings, String> {
+    let client = get_http_client();
+
+    let response = client
+        .get(format!("{}/settings", API_BASE))
+        .header("X-Widget-Secret", WIDGET_SECRET)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status() == 401 {
+        return Err("Widget authentication failed. Check your secret.".to_string());
+    }
+
+    if !response.status().is_success() {
+        return Ok(UserSettings::default());
+    }
+
+    let settings: UserSettings = response
+        .json()
+        .await
+        .unwrap_or_default();
+
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap()
+    }
+
+    fn entry(stopped_at: Option<DateTime<Utc>>, resumed_at: Option<DateTime<Utc>>, duration_minutes: Option<f64>) -> TimeEntry {
+        TimeEntry {
+            id: 1,
+            project_id: 1,
+            project: None,
+            description: None,
+            started_at: format_timestamp(at(10, 0)),
+            stopped_at: stopped_at.map(format_timestamp),
+            resumed_at: resumed_at.map(format_timestamp),
+            duration_minutes,
+            is_billable: true,
+        }
+    }
+
+    #[test]
+    fn running_entry_counts_from_start() {
+        // A duration on a never-resumed running entry is not banked time
+        let running = entry(None, None, Some(5.0));
+        assert_eq!(entry_elapsed_seconds(&running, at(10, 30)), 1800);
+    }
+
+    #[test]
+    fn paused_entry_keeps_banked_duration() {
+        let paused = entry(Some(at(10, 25)), None, Some(25.0));
+        assert_eq!(entry_elapsed_seconds(&paused, at(12, 0)), 1500);
+    }
+
+    #[test]
+    fn resumed_entry_adds_current_session() {
+        // 25 minutes before the pause, then 10 minutes since resuming at 11:00
+        let resumed = entry(None, Some(at(11, 0)), Some(25.0));
+        assert_eq!(entry_elapsed_seconds(&resumed, at(11, 10)), 2100);
+    }
+
+    #[test]
+    fn stopped_after_resume_uses_total_duration() {
+        let stopped = entry(Some(at(11, 10)), Some(at(11, 0)), Some(35.0));
+        assert_eq!(entry_elapsed_seconds(&stopped, at(15, 0)), 2100);
+    }
+
+    #[test]
+    fn session_in_the_future_counts_nothing() {
+        let resumed = entry(None, Some(at(11, 0)), Some(25.0));
+        assert_eq!(entry_elapsed_seconds(&resumed, at(11, 0) - Duration::minutes(1)), 1500);
+    }
+}
@@ -0,0 +1,393 @@
+// Headless companion CLI: drives timers through the same widget API as the app
+
+use chrono::{Duration, Local, NaiveDate, Utc};
+use freshtracks::export::{render_with_rounding, ExportFormat};
+use freshtracks::reports::{parse_date, week_start, ReportSettings, RoundingRule};
+use freshtracks::search::{fetch_catalog, rank_projects};
+use freshtracks::{
+    entry_elapsed_seconds, fetch_time_entries_range, format_timer_text, get_active_timer,
+    get_time_entries, last_stopped_entry, parse_timestamp, project_label, restart_timer, start_timer,
+    stop_timer, TimeEntry, TimeEntryFilter,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+// The app's bundle identifier (tauri.conf.json), which names its config directory
+const APP_IDENTIFIER: &str = "com.freshtracks.menubar";
+
+const USAGE: &str = "Usage: freshtracks <command> [options]
+
+Commands:
+  status                        Show the running timer
+  start <project> [-d TEXT]     Start a timer (project id or fuzzy name, e.g. \"acme web\")
+  stop                          Stop the running timer
+  restart [ENTRY_ID]            Restart an entry (defaults to the last stopped one)
+  log [--week] [--date DATE]    List entries for a day (default today) or its week
+  export [--format csv|json|invoice] [--from DATE] [--to DATE] [--week] [--date DATE] [-o FILE]
+                                Export entries with the app's rounding (default: this week as CSV to stdout)
+  help                          Show this message
+
+Options:
+  --json                        Print machine-readable JSON (status, start, stop, restart, log)
+
+Dates are local and formatted YYYY-MM-DD.";
+
+struct Args {
+    positional: Vec<String>,
+    flags: Vec<(String, Option<String>)>,
+    json: bool,
+}
+
+// Options that take a value
+const VALUE_OPTIONS: [&str; 8] = ["-d", "--description", "--date", "--format", "--from", "--to", "-o", "--output"];
+
+impl Args {
+    fn parse(raw: Vec<String>) -> Result<Self, String> {
+        let mut args = Args { positional: Vec::new(), flags: Vec::new(), json: false };
+        let mut iter = raw.into_iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--json" {
+                args.json = true;
+            } else if let Some((name, value)) = arg.split_once('=').filter(|_| arg.starts_with("--")) {
+                args.flags.push((name.to_string(), Some(value.to_string())));
+            } else if VALUE_OPTIONS.contains(&arg.as_str()) {
+                let value = iter.next().ok_or_else(|| format!("{} needs a value", arg))?;
+                args.flags.push((arg, Some(value)));
+            } else if arg.starts_with('-') && arg.len() > 1 {
+                args.flags.push((arg, None));
+            } else {
+                args.positional.push(arg);
+            }
+        }
+        Ok(args)
+    }
+
+    fn value(&self, names: &[&str]) -> Option<&str> {
+        self.flags
+            .iter()
+            .rev()
+            .find(|(name, _)| names.contains(&name.as_str()))
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.flags.iter().any(|(n, _)| n == name)
+    }
+
+    // Reject options the command doesn't understand
+    fn only(&self, allowed: &[&str]) -> Result<(), String> {
+        match self.flags.iter().find(|(name, _)| !allowed.contains(&name.as_str())) {
+            Some((name, _)) => Err(format!("Unknown option {}", name)),
+            None => Ok(()),
+        }
+    }
+}
+
+fn print_json<T: Serialize>(value: &T) -> Result<(), String> {
+    let text = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    println!("{}", text);
+    Ok(())
+}
+
+fn local_time(value: &str) -> String {
+    parse_timestamp(value)
+        .map(|dt| dt.with_timezone(&Local).format("%H:%M").to_string())
+        .unwrap_or_else(|| "??:??".to_string())
+}
+
+fn describe(entry: &TimeEntry) -> String {
+    let project = project_label(entry.project.as_ref());
+    match entry.description.as_deref().filter(|d| !d.is_empty()) {
+        Some(description) => format!("{} - {}", project, description),
+        None => project,
+    }
+}
+
+fn print_entry(verb: &str, entry: &TimeEntry, json: bool) -> Result<(), String> {
+    if json {
+        return print_json(entry);
+    }
+    println!("{} {} ({})", verb, describe(entry), format_timer_text(entry_elapsed_seconds(entry, Utc::now())));
+    Ok(())
+}
+
+async fn status(args: &Args) -> Result<(), String> {
+    args.only(&[])?;
+    let timer = get_active_timer().await?;
+    if args.json {
+        return print_json(&timer);
+    }
+    match timer {
+        Some(entry) => println!(
+            "Running: {} since {} ({})",
+            describe(&entry),
+            local_time(&entry.started_at),
+            format_timer_text(entry_elapsed_seconds(&entry, Utc::now()))
+        ),
+        None => println!("No timer running"),
+    }
+    Ok(())
+}
+
+async fn stop_running() -> Result<Option<TimeEntry>, String> {
+    match get_active_timer().await? {
+        Some(running) => stop_timer(running.id).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+async fn start(args: &Args) -> Result<(), String> {
+    args.only(&["-d", "--description"])?;
+    let query = args.positional.get(1..).unwrap_or_default().join(" ");
+    if query.is_empty() {
+        return Err("start needs a project id or name".to_string());
+    }
+
+    let project_id = match query.parse::<i32>() {
+        Ok(id) => id,
+        Err(_) => {
+            let projects = fetch_catalog().await?;
+            let best = rank_projects(&projects, &HashMap::new(), &query, 1).into_iter().next();
+            let best = best.ok_or_else(|| format!("No project matches \"{}\"", query))?;
+            if !args.json {
+                println!("Matched {}", best.label);
+            }
+            best.project.id
+        }
+    };
+
+    stop_running().await?;
+    let description = args.value(&["-d", "--description"]).map(str::to_string);
+    let started = start_timer(project_id, description).await?;
+    print_entry("Started", &started, args.json)
+}
+
+async fn stop(args: &Args) -> Result<(), String> {
+    args.only(&[])?;
+    match stop_running().await? {
+        Some(stopped) => print_entry("Stopped", &stopped, args.json),
+        None if args.json => print_json(&None::<TimeEntry>),
+        None => Err("No timer is running".to_string()),
+    }
+}
+
+async fn restart(args: &Args) -> Result<(), String> {
+    args.only(&[])?;
+    let entry_id = match args.positional.get(1) {
+        Some(value) => value.parse::<i32>().map_err(|_| format!("Invalid entry id {}", value))?,
+        None => {
+            let entries = get_time_entries().await?;
+            last_stopped_entry(&entries)
+                .map(|e| e.id)
+                .ok_or_else(|| "No stopped entry to restart".to_string())?
+        }
+    };
+
+    let running = get_active_timer().await?;
+    if let Some(running) = running.filter(|r| r.id != entry_id) {
+        stop_timer(running.id).await?;
+    }
+    let started = restart_timer(entry_id).await?;
+    print_entry("Restarted", &started, args.json)
+}
+
+// Local date range for --date (default today), widened to its week with
+// --week, or when `default_week` is set and no --date is given
+fn selected_range(args: &Args, default_week: bool) -> Result<(NaiveDate, NaiveDate), String> {
+    let date = match args.value(&["--date"]) {
+        Some(value) => parse_date(value)?,
+        None => Local::now().date_naive(),
+    };
+    if args.has("--week") || (default_week && !args.has("--date")) {
+        let start = week_start(date);
+        Ok((start, start + Duration::days(6)))
+    } else {
+        Ok((date, date))
+    }
+}
+
+async fn log(args: &Args) -> Result<(), String> {
+    args.only(&["--week", "--date"])?;
+    let (from, to) = selected_range(args, false)?;
+    let mut entries = fetch_time_entries_range(&TimeEntryFilter {
+        from: Some(from.to_string()),
+        to: Some(to.to_string()),
+        ..Default::default()
+    }).await?;
+    entries.sort_by_key(|e| parse_timestamp(&e.started_at));
+
+    if args.json {
+        return print_json(&entries);
+    }
+
+    let now = Utc::now();
+    let mut total = 0;
+    let mut current_day: Option<NaiveDate> = None;
+    let mut day_total = 0;
+
+    for entry in &entries {
+        let Some(started) = parse_timestamp(&entry.started_at) else {
+            continue;
+        };
+        let day = started.with_timezone(&Local).date_naive();
+        if current_day != Some(day) {
+            if current_day.is_some() {
+                println!("  Total {}\n", format_timer_text(day_total));
+            }
+            println!("{}", day.format("%a %b %-d"));
+            current_day = Some(day);
+            day_total = 0;
+        }
+
+        let elapsed = entry_elapsed_seconds(entry, now);
+        let stopped = entry.stopped_at.as_deref().map(local_time).unwrap_or_else(|| "now".to_string());
+        println!(
+            "  {}-{}  {:>8}  {}",
+            local_time(&entry.started_at),
+            stopped,
+            format_timer_text(elapsed),
+            describe(entry)
+        );
+        day_total += elapsed;
+        total += elapsed;
+    }
+
+    match current_day {
+        Some(_) => println!("  Total {}", format_timer_text(day_total)),
+        None => println!("No entries"),
+    }
+    if from != to {
+        println!("\nWeek total {}", format_timer_text(total));
+    }
+    Ok(())
+}
+
+async fn export(args: &Args) -> Result<(), String> {
+    args.only(&["--format", "--from", "--to", "--week", "--date", "-o", "--output"])?;
+    let format = match args.value(&["--format"]).unwrap_or("csv") {
+        "csv" => ExportFormat::Csv,
+        "json" => ExportFormat::Json,
+        "invoice" => ExportFormat::Invoice,
+        other => return Err(format!("Unknown format {} (use csv, json or invoice)", other)),
+    };
+
+    // Explicit --from/--to win over --week/--date (default this week)
+    let (default_from, default_to) = selected_range(args, true)?;
+    let from = match args.value(&["--from"]) {
+        Some(value) => parse_date(value)?,
+        None => default_from,
+    };
+    let to = match args.value(&["--to"]) {
+        Some(value) => parse_date(value)?,
+        None => default_to.max(from),
+    };
+
+    let filter = TimeEntryFilter {
+        from: Some(from.to_string()),
+        to: Some(to.to_string()),
+        ..Default::default()
+    };
+    let content = render_with_rounding(&filter, format, saved_rounding()).await?;
+
+    match args.value(&["-o", "--output"]) {
+        Some(path) => {
+            std::fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+            eprintln!("Exported {} to {} to {}", from, to, path);
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+// The app's preferences file; only the report settings matter here
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct SavedPreferences {
+    reports: ReportSettings,
+}
+
+fn preferences_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_IDENTIFIER).join("preferences.json"))
+}
+
+fn rounding_from_preferences(text: &str) -> Result<RoundingRule, String> {
+    serde_json::from_str::<SavedPreferences>(text)
+        .map(|prefs| prefs.reports.rounding)
+        .map_err(|e| e.to_string())
+}
+
+// Rounding configured in the app, so exports match the ones it makes; none if unset
+fn saved_rounding() -> RoundingRule {
+    let Some(path) = preferences_path() else {
+        return RoundingRule::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(text) => rounding_from_preferences(&text).unwrap_or_else(|e| {
+            eprintln!("Ignoring invalid preferences {}: {}", path.display(), e);
+            RoundingRule::default()
+        }),
+        Err(_) => RoundingRule::default(),
+    }
+}
+
+async fn run(args: Args) -> Result<(), String> {
+    match args.positional.first().map(String::as_str) {
+        Some("status") => status(&args).await,
+        Some("start") => start(&args).await,
+        Some("stop") => stop(&args).await,
+        Some("restart") => restart(&args).await,
+        Some("log") => log(&args).await,
+        Some("export") => export(&args).await,
+        Some("help") | None => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(format!("Unknown command {}\n\n{}", other, USAGE)),
+    }
+}
+
+fn main() -> ExitCode {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    if raw.iter().any(|a| a == "-h" || a == "--help") {
+        println!("{}", USAGE);
+        return ExitCode::SUCCESS;
+    }
+
+    let result = Args::parse(raw).and_then(|args| {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Failed to start runtime: {}", e))?;
+        runtime.block_on(run(args))
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("freshtracks: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use freshtracks::reports::RoundingMode;
+
+    #[test]
+    fn rounding_read_from_app_preferences() {
+        let json = r#"{"shortcuts": [], "reports": {"rounding": {"increment_minutes": 15, "mode": "up"}}}"#;
+        assert_eq!(
+            rounding_from_preferences(json),
+            Ok(RoundingRule { increment_minutes: 15, mode: RoundingMode::Up })
+        );
+    }
+
+    #[test]
+    fn rounding_defaults_when_unset() {
+        assert_eq!(rounding_from_preferences(r#"{"idle": {}}"#), Ok(RoundingRule::default()));
+        assert!(rounding_from_preferences("not json").is_err());
+    }
+}
@@ -0,0 +1,294 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{entry_elapsed_seconds, parse_timestamp, project_label, TimeEntry};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingMode {
+    #[default]
+    Nearest,
+    Up,
+    Down,
+}
+
+// Rounding applied to each entry's duration before totals are summed
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(default)]
+pub struct RoundingRule {
+    // 0 disables rounding
+    pub increment_minutes: u32,
+    pub mode: RoundingMode,
+}
+
+impl Default for RoundingRule {
+    fn default() -> Self {
+        Self {
+            increment_minutes: 0,
+            mode: RoundingMode::Nearest,
+        }
+    }
+}
+
+impl RoundingRule {
+    pub fn apply(&self, minutes: f64) -> f64 {
+        if self.increment_minutes == 0 {
+            return minutes;
+        }
+        let increment = self.increment_minutes as f64;
+        let steps = minutes / increment;
+        let steps = match self.mode {
+            RoundingMode::Nearest => steps.round(),
+            RoundingMode::Up => steps.ceil(),
+            RoundingMode::Down => steps.floor(),
+        };
+        steps * increment
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ReportSettings {
+    pub rounding: RoundingRule,
+}
+
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct Totals {
+    pub minutes: f64,
+    pub hours: f64,
+    pub billable_hours: f64,
+    // None when no billable entry had a client rate
+    pub revenue: Option<f64>,
+    pub entry_count: u32,
+}
+
+impl Totals {
+    fn add(&mut self, minutes: f64, is_billable: bool, hourly_rate: Option<f64>) {
+        self.minutes += minutes;
+        self.entry_count += 1;
+        if is_billable {
+            self.billable_hours += minutes / 60.0;
+            if let Some(rate) = hourly_rate {
+                *self.revenue.get_or_insert(0.0) += minutes / 60.0 * rate;
+            }
+        }
+    }
+
+    // Hours to 2 decimals, revenue to cents
+    fn finish(mut self) -> Self {
+        self.hours = round_to(self.minutes / 60.0, 2);
+        self.billable_hours = round_to(self.billable_hours, 2);
+        self.revenue = self.revenue.map(|r| round_to(r, 2));
+        self
+    }
+}
+
+pub(crate) fn round_to(value: f64, decimals: i32) -> f64 {
+    let factor = 10f64.powi(decimals);
+    (value * factor).round() / factor
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ReportRow {
+    // Date ("YYYY-MM-DD", weeks keyed by their first day), client id or project id
+    pub key: String,
+    pub label: String,
+    pub totals: Totals,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct Report {
+    pub from: String,
+    pub to: String,
+    pub rounding: RoundingRule,
+    pub total: Totals,
+    pub by_day: Vec<ReportRow>,
+    pub by_week: Vec<ReportRow>,
+    pub by_client: Vec<ReportRow>,
+    pub by_project: Vec<ReportRow>,
+}
+
+// Monday of the week containing `date`
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+pub fn parse_date(value: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|e| format!("Invalid date {}: {}", value, e))
+}
+
+// Local calendar date an entry belongs to (the day it started)
+pub fn entry_local_date(entry: &TimeEntry) -> Option<NaiveDate> {
+    parse_timestamp(&entry.started_at).map(|dt| dt.with_timezone(&Local).date_naive())
+}
+
+fn add_to(rows: &mut Vec<ReportRow>, key: String, label: String, minutes: f64, entry: &TimeEntry, rate: Option<f64>) {
+    let index = match rows.iter().position(|r| r.key == key) {
+        Some(i) => i,
+        None => {
+            rows.push(ReportRow { key, label, totals: Totals::default() });
+            rows.len() - 1
+        }
+    };
+    rows[index].totals.add(minutes, entry.is_billable, rate);
+}
+
+fn finish_rows(rows: Vec<ReportRow>) -> Vec<ReportRow> {
+    rows.into_iter()
+        .map(|r| ReportRow { totals: r.totals.finish(), ..r })
+        .collect()
+}
+
+// Aggregate entries that started between `from` and `to` (inclusive, local dates)
+pub fn build_report(entries: &[TimeEntry], from: NaiveDate, to: NaiveDate, rounding: RoundingRule, now: DateTime<Utc>) -> Report {
+    let mut total = Totals::default();
+    let mut by_day = Vec::new();
+    let mut by_week = Vec::new();
+    let mut by_client = Vec::new();
+    let mut by_project = Vec::new();
+
+    for entry in entries {
+        let Some(date) = entry_local_date(entry) else {
+            continue;
+        };
+        if date < from || date > to {
+            continue;
+        }
+
+        let minutes = rounding.apply(entry_elapsed_seconds(entry, now) as f64 / 60.0);
+        let client = entry.project.as_ref().and_then(|p| p.client.as_ref());
+        let rate = client.and_then(|c| c.hourly_rate);
+
+        total.add(minutes, entry.is_billable, rate);
+
+        add_to(&mut by_day, date.to_string(), date.format("%a %b %-d").to_string(), minutes, entry, rate);
+
+        let week = week_start(date);
+        add_to(&mut by_week, week.to_string(), format!("Week of {}", week.format("%b %-d")), minutes, entry, rate);
+
+        let (client_key, client_label) = match client {
+            Some(c) => (c.id.to_string(), c.name.clone()),
+            None => ("none".to_string(), "No client".to_string()),
+        };
+        add_to(&mut by_client, client_key, client_label, minutes, entry, rate);
+
+        add_to(&mut by_project, entry.project_id.to_string(), project_label(entry.project.as_ref()), minutes, entry, rate);
+    }
+
+    by_day.sort_by(|a, b| a.key.cmp(&b.key));
+    by_week.sort_by(|a, b| a.key.cmp(&b.key));
+    by_client.sort_by(|a, b| b.totals.minutes.total_cmp(&a.totals.minutes));
+    by_project.sort_by(|a, b| b.totals.minutes.total_cmp(&a.totals.minutes));
+
+    Report {
+        from: from.to_string(),
+        to: to.to_string(),
+        rounding,
+        total: total.finish(),
+        by_day: finish_rows(by_day),
+        by_week: finish_rows(by_week),
+        by_client: finish_rows(by_client),
+        by_project: finish_rows(by_project),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format_timestamp, Client, Project};
+    use chrono::TimeZone;
+
+    fn rule(increment_minutes: u32, mode: RoundingMode) -> RoundingRule {
+        RoundingRule { increment_minutes, mode }
+    }
+
+    fn date(value: &str) -> NaiveDate {
+        parse_date(value).unwrap()
+    }
+
+    fn project(id: i32, hourly_rate: Option<f64>) -> Project {
+        Project {
+            id,
+            name: format!("Project {}", id),
+            client: Some(Client { id: 10, name: "Acme".to_string(), hourly_rate }),
+        }
+    }
+
+    // A stopped entry started at noon local time on `day`
+    fn entry(day: &str, minutes: f64, project: Project, is_billable: bool) -> TimeEntry {
+        let started = Local.from_local_datetime(&date(day).and_hms_opt(12, 0, 0).unwrap()).unwrap();
+        let started = started.with_timezone(&Utc);
+        TimeEntry {
+            id: 1,
+            project_id: project.id,
+            project: Some(project),
+            description: None,
+            started_at: format_timestamp(started),
+            stopped_at: Some(format_timestamp(started + Duration::seconds((minutes * 60.0) as i64))),
+            resumed_at: None,
+            duration_minutes: Some(minutes),
+            is_billable,
+        }
+    }
+
+    #[test]
+    fn rounding_modes() {
+        assert_eq!(rule(15, RoundingMode::Nearest).apply(7.0), 0.0);
+        assert_eq!(rule(15, RoundingMode::Nearest).apply(8.0), 15.0);
+        assert_eq!(rule(15, RoundingMode::Up).apply(1.0), 15.0);
+        assert_eq!(rule(15, RoundingMode::Down).apply(29.0), 15.0);
+        assert_eq!(rule(0, RoundingMode::Up).apply(7.5), 7.5);
+    }
+
+    #[test]
+    fn rounds_each_entry_before_summing() {
+        let entries = vec![
+            entry("2024-06-04", 8.0, project(1, None), true),
+            entry("2024-06-04", 8.0, project(1, None), true),
+        ];
+        let report = build_report(&entries, date("2024-06-03"), date("2024-06-09"), rule(15, RoundingMode::Nearest), Utc::now());
+        assert_eq!(report.total.minutes, 30.0);
+        assert_eq!(report.total.hours, 0.5);
+        assert_eq!(report.total.entry_count, 2);
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        assert_eq!(week_start(date("2024-06-09")), date("2024-06-03"));
+        assert_eq!(week_start(date("2024-06-10")), date("2024-06-10"));
+
+        let entries = vec![
+            entry("2024-06-09", 60.0, project(1, None), true),
+            entry("2024-06-10", 30.0, project(1, None), true),
+            // Outside the range
+            entry("2024-06-12", 45.0, project(1, None), true),
+        ];
+        let report = build_report(&entries, date("2024-06-09"), date("2024-06-10"), RoundingRule::default(), Utc::now());
+
+        let weeks: Vec<(&str, f64)> = report.by_week.iter().map(|r| (r.key.as_str(), r.totals.minutes)).collect();
+        assert_eq!(weeks, vec![("2024-06-03", 60.0), ("2024-06-10", 30.0)]);
+        assert_eq!(report.by_week[1].label, "Week of Jun 10");
+        let days: Vec<&str> = report.by_day.iter().map(|r| r.key.as_str()).collect();
+        assert_eq!(days, vec!["2024-06-09", "2024-06-10"]);
+        assert_eq!(report.total.minutes, 90.0);
+    }
+
+    #[test]
+    fn revenue_counts_billable_time_only() {
+        let entries = vec![
+            entry("2024-06-04", 90.0, project(1, Some(100.0)), true),
+            entry("2024-06-04", 30.0, project(1, Some(100.0)), false),
+            entry("2024-06-05", 20.0, project(2, None), true),
+        ];
+        let report = build_report(&entries, date("2024-06-03"), date("2024-06-09"), RoundingRule::default(), Utc::now());
+
+        assert_eq!(report.total.hours, 2.33);
+        assert_eq!(report.total.billable_hours, 1.83);
+        assert_eq!(report.total.revenue, Some(150.0));
+
+        // Projects sorted by time, largest first
+        let projects: Vec<(&str, Option<f64>)> = report.by_project.iter().map(|r| (r.key.as_str(), r.totals.revenue)).collect();
+        assert_eq!(projects, vec![("1", Some(150.0)), ("2", None)]);
+        assert_eq!(report.by_client.len(), 1);
+    }
+}
@@ -0,0 +1,171 @@
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::collections::HashMap;
+
+use crate::fuzzy;
+use crate::{get_client_projects, get_clients, parse_timestamp, Client, Project, TimeEntry};
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ProjectMatch {
+    pub project: Project,
+    // "Client / Project", or just the project name without a client
+    pub label: String,
+    pub score: f64,
+}
+
+// Every client's projects, with the client attached to each project
+pub async fn fetch_catalog() -> Result<Vec<Project>, String> {
+    let clients = get_clients().await?;
+    let results = join_all(clients.iter().map(|c| get_client_projects(c.id))).await;
+
+    let mut projects = Vec::new();
+    for (client, result) in clients.iter().zip(results) {
+        match result {
+            Ok(client_projects) => {
+                for mut project in client_projects {
+                    if project.client.is_none() {
+                        project.client = Some(client.clone());
+                    }
+                    projects.push(project);
+                }
+            }
+            Err(e) => eprintln!("Failed to fetch projects for client {}: {}", client.id, e),
+        }
+    }
+    Ok(projects)
+}
+
+pub fn project_search_label(project: &Project) -> String {
+    match &project.client {
+        Some(Client { name, .. }) if !name.is_empty() => format!("{} / {}", name, project.name),
+        _ => project.name.clone(),
+    }
+}
+
+// Usage weight per project: how often and how recently it was tracked, plus
+// its position in the server's recent projects list
+pub fn usage_scores(recent_projects: &[Project], entries: &[TimeEntry], now: DateTime<Utc>) -> HashMap<i32, f64> {
+    let mut scores: HashMap<i32, f64> = HashMap::new();
+
+    for (rank, project) in recent_projects.iter().enumerate() {
+        *scores.entry(project.id).or_insert(0.0) += 1.0 / (1.0 + rank as f64);
+    }
+
+    let mut counts: HashMap<i32, (u32, DateTime<Utc>)> = HashMap::new();
+    for entry in entries {
+        let Some(started_at) = parse_timestamp(&entry.started_at) else {
+            continue;
+        };
+        let stat = counts.entry(entry.project_id).or_insert((0, started_at));
+        stat.0 += 1;
+        stat.1 = stat.1.max(started_at);
+    }
+    for (project_id, (count, last_used)) in counts {
+        let days_ago = (now - last_used).num_hours().max(0) as f64 / 24.0;
+        *scores.entry(project_id).or_insert(0.0) += (1.0 + count as f64).ln() * 0.5 + 1.0 / (1.0 + days_ago);
+    }
+
+    scores
+}
+
+// Projects whose "client / project" label matches `query`, best first
+pub fn rank_projects(projects: &[Project], usage: &HashMap<i32, f64>, query: &str, limit: usize) -> Vec<ProjectMatch> {
+    let mut matches: Vec<ProjectMatch> = projects
+        .iter()
+        .filter_map(|project| {
+            let label = project_search_label(project);
+            let quality = fuzzy::score(query, &label)?;
+            let usage = usage.get(&project.id).copied().unwrap_or(0.0);
+            Some(ProjectMatch {
+                project: project.clone(),
+                label,
+                score: quality * 2.0 + usage,
+            })
+        })
+        .collect();
+
+    matches.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.label.cmp(&b.label)));
+    matches.truncate(limit);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_timestamp;
+
+    fn project(id: i32, name: &str, client: Option<&str>) -> Project {
+        Project {
+            id,
+            name: name.to_string(),
+            client: client.map(|name| Client { id: id * 10, name: name.to_string(), hourly_rate: None }),
+        }
+    }
+
+    fn catalog() -> Vec<Project> {
+        vec![
+            project(1, "Website Redesign", Some("Acme Corp")),
+            project(2, "Mobile App", Some("Acme Corp")),
+            project(3, "Website", Some("Globex")),
+            project(4, "Internal", None),
+        ]
+    }
+
+    fn labels(matches: &[ProjectMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.label.as_str()).collect()
+    }
+
+    #[test]
+    fn label_includes_client() {
+        assert_eq!(project_search_label(&project(1, "Website", Some("Acme"))), "Acme / Website");
+        assert_eq!(project_search_label(&project(1, "Website", Some(""))), "Website");
+        assert_eq!(project_search_label(&project(1, "Website", None)), "Website");
+    }
+
+    #[test]
+    fn matches_client_and_project_words() {
+        let matches = rank_projects(&catalog(), &HashMap::new(), "acme web", 10);
+        assert_eq!(labels(&matches), vec!["Acme Corp / Website Redesign"]);
+    }
+
+    #[test]
+    fn ties_sorted_by_label_and_limited() {
+        let matches = rank_projects(&catalog(), &HashMap::new(), "", 3);
+        assert_eq!(
+            labels(&matches),
+            vec!["Acme Corp / Mobile App", "Acme Corp / Website Redesign", "Globex / Website"]
+        );
+    }
+
+    #[test]
+    fn usage_breaks_equal_matches() {
+        let usage = HashMap::from([(3, 1.0)]);
+        let matches = rank_projects(&catalog(), &usage, "website", 10);
+        assert_eq!(labels(&matches), vec!["Globex / Website", "Acme Corp / Website Redesign"]);
+    }
+
+    #[test]
+    fn usage_counts_recent_rank_and_entries() {
+        let now = parse_timestamp("2024-06-15T12:00:00Z").unwrap();
+        let entry = |project_id: i32| TimeEntry {
+            id: project_id,
+            project_id,
+            project: None,
+            description: None,
+            started_at: format_timestamp(now),
+            stopped_at: None,
+            resumed_at: None,
+            duration_minutes: None,
+            is_billable: true,
+        };
+        let recent = vec![project(2, "Mobile App", None), project(1, "Website Redesign", None)];
+        let scores = usage_scores(&recent, &[entry(1), entry(1), entry(3)], now);
+
+        assert!((scores[&2] - 1.0).abs() < 1e-9);
+        // Second in the recent list, plus two entries today
+        assert!((scores[&1] - (0.5 + 3f64.ln() * 0.5 + 1.0)).abs() < 1e-9);
+        assert!((scores[&3] - (2f64.ln() * 0.5 + 1.0)).abs() < 1e-9);
+        assert!(!scores.contains_key(&4));
+    }
+}
@@ -0,0 +1,452 @@
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_dialog::DialogExt;
+
+use crate::entries::create_past_entry;
+use crate::{
+    entry_elapsed_seconds, fetch_time_entries_range, format_timestamp, get_recent_projects,
+    parse_timestamp, project_label, refresh_tray_menu_data, Project, TimeEntry, TimeEntryFilter,
+};
+
+// iCalendar lines longer than this (in octets) must be folded
+const ICS_LINE_LIMIT: usize = 75;
+
+// A calendar event proposed as a time entry, waiting for the user to confirm it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DraftEntry {
+    pub uid: String,
+    pub summary: String,
+    pub started_at: String,
+    pub stopped_at: String,
+    pub project_id: Option<i32>,
+    pub project_name: Option<String>,
+    // 0..1, how well the event title matched the project
+    pub match_score: f64,
+    pub description: Option<String>,
+    pub is_billable: bool,
+}
+
+// A VEVENT read from an .ics file
+#[derive(Debug, Clone, Default)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub all_day: bool,
+}
+
+// Escape TEXT values (RFC 5545 3.3.11)
+pub fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+pub fn unescape_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+// Fold a content line at 75 octets without splitting UTF-8 characters
+fn fold_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > ICS_LINE_LIMIT {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += c.len_utf8();
+    }
+    out.push_str("\r\n");
+    out
+}
+
+fn format_ics_time(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+// Build a calendar with one VEVENT per entry; running entries end now
+pub fn entries_to_ics(entries: &[TimeEntry], now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    out.push_str(&fold_line("BEGIN:VCALENDAR"));
+    out.push_str(&fold_line("VERSION:2.0"));
+    out.push_str(&fold_line("PRODID:-//FreshTracks//Menubar//EN"));
+    out.push_str(&fold_line("CALSCALE:GREGORIAN"));
+
+    for entry in entries {
+        let Some(start) = parse_timestamp(&entry.started_at) else {
+            continue;
+        };
+        // Paused/resumed entries may have a stopped_at earlier than their tracked time, so use the duration
+        let end = start + chrono::Duration::seconds(entry_elapsed_seconds(entry, now).max(0));
+
+        // "Client - Project", like the tray and reports
+        let summary = project_label(entry.project.as_ref());
+
+        out.push_str(&fold_line("BEGIN:VEVENT"));
+        out.push_str(&fold_line(&format!("UID:freshtracks-entry-{}@freshtracks", entry.id)));
+        out.push_str(&fold_line(&format!("DTSTAMP:{}", format_ics_time(now))));
+        out.push_str(&fold_line(&format!("DTSTART:{}", format_ics_time(start))));
+        out.push_str(&fold_line(&format!("DTEND:{}", format_ics_time(end))));
+        out.push_str(&fold_line(&format!("SUMMARY:{}", escape_text(&summary))));
+        if let Some(description) = entry.description.as_deref().filter(|d| !d.is_empty()) {
+            out.push_str(&fold_line(&format!("DESCRIPTION:{}", escape_text(description))));
+        }
+        let billable = if entry.is_billable { "Billable" } else { "Non-billable" };
+        out.push_str(&fold_line(&format!("CATEGORIES:{}", billable)));
+        out.push_str(&fold_line("END:VEVENT"));
+    }
+
+    out.push_str(&fold_line("END:VCALENDAR"));
+    out
+}
+
+// Parse DTSTART/DTEND values: UTC ("...Z"), floating/TZID (treated as local) or all-day dates
+fn parse_ics_time(params: &str, value: &str) -> (Option<DateTime<Utc>>, bool) {
+    if params.contains("VALUE=DATE") || value.len() == 8 {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok();
+        let start = date
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+            .map(|dt| dt.with_timezone(&Utc));
+        return (start, true);
+    }
+
+    if let Some(utc) = value.strip_suffix('Z') {
+        let dt = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok().map(|dt| dt.and_utc());
+        return (dt, false);
+    }
+
+    let dt = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S")
+        .ok()
+        .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+        .map(|dt| dt.with_timezone(&Utc));
+    (dt, false)
+}
+
+pub fn parse_ics(text: &str) -> Vec<CalendarEvent> {
+    // Unfold continuation lines (leading space or tab)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in text.split('\n') {
+        let raw = raw.trim_end_matches('\r');
+        if let Some(rest) = raw.strip_prefix(' ').or_else(|| raw.strip_prefix('\t')) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+            }
+        } else {
+            lines.push(raw.to_string());
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<CalendarEvent> = None;
+
+    for line in lines {
+        let Some((name_params, value)) = line.split_once(':') else {
+            continue;
+        };
+        let (name, params) = name_params.split_once(';').unwrap_or((name_params, ""));
+
+        match (name.to_ascii_uppercase().as_str(), current.as_mut()) {
+            ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => current = Some(CalendarEvent::default()),
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => events.extend(current.take()),
+            ("UID", Some(event)) => event.uid = value.to_string(),
+            ("SUMMARY", Some(event)) => event.summary = unescape_text(value),
+            ("DESCRIPTION", Some(event)) => event.description = Some(unescape_text(value)),
+            ("DTSTART", Some(event)) => {
+                let (start, all_day) = parse_ics_time(params, value);
+                event.start = start;
+                event.all_day = all_day;
+            }
+            ("DTEND", Some(event)) => event.end = parse_ics_time(params, value).0,
+            _ => {}
+        }
+    }
+
+    events
+}
+
+fn tokens(value: &str) -> Vec<String> {
+    value
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.len() > 1)
+        .map(|t| t.to_lowercase())
+        .collect()
+}
+
+// Best project for an event title: share of the project's (and client's) words found in the title
+pub fn match_project<'a>(title: &str, projects: &'a [Project]) -> Option<(&'a Project, f64)> {
+    let title_tokens = tokens(title);
+    if title_tokens.is_empty() {
+        return None;
+    }
+
+    projects
+        .iter()
+        .filter_map(|project| {
+            let project_tokens = tokens(&project.name);
+            if project_tokens.is_empty() {
+                return None;
+            }
+            let hits = project_tokens.iter().filter(|t| title_tokens.contains(t)).count();
+            let mut score = hits as f64 / project_tokens.len() as f64;
+
+            // A client name in the title counts for a little extra
+            if let Some(client) = &project.client {
+                let client_tokens = tokens(&client.name);
+                if !client_tokens.is_empty() && client_tokens.iter().all(|t| title_tokens.contains(t)) {
+                    score = (score + 0.25).min(1.0);
+                }
+            }
+            (score > 0.0).then_some((project, score))
+        })
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+// Turn timed events into drafts, skipping all-day events and ones already tracked
+pub fn propose_drafts(events: &[CalendarEvent], projects: &[Project], existing: &[TimeEntry]) -> Vec<DraftEntry> {
+    let mut drafts: Vec<DraftEntry> = events
+        .iter()
+        .filter(|e| !e.all_day)
+        .filter_map(|event| {
+            let (start, end) = (event.start?, event.end?);
+            if end <= start {
+                return None;
+            }
+
+            // An entry on the same interval was probably logged for this meeting already
+            let already_tracked = existing.iter().any(|entry| {
+                let entry_start = parse_timestamp(&entry.started_at);
+                let entry_end = entry.stopped_at.as_deref().and_then(parse_timestamp);
+                matches!((entry_start, entry_end), (Some(s), Some(e)) if s < end && e > start && (s - start).num_minutes().abs() <= 5)
+            });
+            if already_tracked {
+                return None;
+            }
+
+            let matched = match_project(&event.summary, projects);
+            Some(DraftEntry {
+                uid: event.uid.clone(),
+                summary: event.summary.clone(),
+                started_at: format_timestamp(start),
+                stopped_at: format_timestamp(end),
+                project_id: matched.map(|(p, _)| p.id),
+                project_name: matched.map(|(p, _)| p.name.clone()),
+                match_score: matched.map(|(_, score)| score).unwrap_or(0.0),
+                description: Some(event.summary.clone()).filter(|s| !s.is_empty()),
+                is_billable: true,
+            })
+        })
+        .collect();
+
+    drafts.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    drafts
+}
+
+// Export entries in the range to an .ics file chosen in a save dialog
+#[tauri::command]
+pub async fn export_ics(app: AppHandle, filter: TimeEntryFilter) -> Result<Option<String>, String> {
+    let entries = fetch_time_entries_range(&filter).await?;
+    let content = entries_to_ics(&entries, Utc::now());
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_file_name("freshtracks.ics")
+        .add_filter("Calendar", &["ics"])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+
+    let Some(path) = rx.await.map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    println!("Exported {} entries to {}", entries.len(), path.display());
+    Ok(Some(path.display().to_string()))
+}
+
+// Read an .ics file (picked in a dialog when no path is given) and propose draft entries
+#[tauri::command]
+pub async fn import_ics(app: AppHandle, path: Option<String>) -> Result<Vec<DraftEntry>, String> {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => {
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            app.dialog()
+                .file()
+                .add_filter("Calendar", &["ics"])
+                .pick_file(move |path| {
+                    let _ = tx.send(path);
+                });
+            match rx.await.map_err(|e| e.to_string())? {
+                Some(path) => path.into_path().map_err(|e| e.to_string())?,
+                None => return Ok(Vec::new()),
+            }
+        }
+    };
+
+    let text = std::fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let events = parse_ics(&text);
+
+    let projects = get_recent_projects().await?;
+
+    // Compare against entries around the imported events to skip ones already logged
+    let from = events.iter().filter_map(|e| e.start).min();
+    let to = events.iter().filter_map(|e| e.end).max();
+    let existing = match (from, to) {
+        (Some(from), Some(to)) => fetch_time_entries_range(&TimeEntryFilter {
+            from: Some(from.with_timezone(&Local).date_naive().to_string()),
+            to: Some(to.with_timezone(&Local).date_naive().to_string()),
+            ..Default::default()
+        }).await.unwrap_or_default(),
+        _ => Vec::new(),
+    };
+
+    Ok(propose_drafts(&events, &projects, &existing))
+}
+
+// Create entries for the drafts the user confirmed (each needs a project)
+#[tauri::command]
+pub async fn confirm_calendar_drafts(app: AppHandle, drafts: Vec<DraftEntry>) -> Result<Vec<TimeEntry>, String> {
+    let mut created = Vec::new();
+    let mut errors = Vec::new();
+
+    for draft in drafts {
+        let Some(project_id) = draft.project_id else {
+            errors.push(format!("{}: no project selected", draft.summary));
+            continue;
+        };
+        match create_past_entry(project_id, draft.description, draft.started_at, draft.stopped_at, draft.is_billable).await {
+            Ok(entry) => created.push(entry),
+            Err(e) => errors.push(format!("{}: {}", draft.summary, e)),
+        }
+    }
+
+    if !created.is_empty() {
+        let _ = app.emit("timer-toggled", ());
+        refresh_tray_menu_data(&app).await;
+    }
+
+    if created.is_empty() && !errors.is_empty() {
+        return Err(errors.join("\n"));
+    }
+    for error in &errors {
+        eprintln!("Failed to create entry from calendar draft {}", error);
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+
+    fn at(value: &str) -> DateTime<Utc> {
+        parse_timestamp(value).unwrap()
+    }
+
+    #[test]
+    fn parse_unfolds_and_unescapes() {
+        let ics = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\n\
+UID:abc@example.com\r\n\
+SUMMARY:Planning\\, Q3\\; budget\r\n\
+DESCRIPTION:Line one\\nLine two with a long\r\n  folded tail\r\n\
+DTSTART:20240301T140000Z\r\n\
+DTEND:20240301T150000Z\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+        let events = parse_ics(ics);
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.uid, "abc@example.com");
+        assert_eq!(event.summary, "Planning, Q3; budget");
+        assert_eq!(event.description.as_deref(), Some("Line one\nLine two with a long folded tail"));
+        assert_eq!(event.start, Some(at("2024-03-01T14:00:00Z")));
+        assert_eq!(event.end, Some(at("2024-03-01T15:00:00Z")));
+        assert!(!event.all_day);
+    }
+
+    #[test]
+    fn parse_all_day_and_ignores_outside_events() {
+        let ics = "SUMMARY:Not in an event\n\
+BEGIN:VEVENT\n\
+SUMMARY:Holiday\n\
+DTSTART;VALUE=DATE:20240704\n\
+END:VEVENT\n";
+        let events = parse_ics(ics);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].summary, "Holiday");
+        assert!(events[0].all_day);
+        assert!(events[0].start.is_some());
+    }
+
+    #[test]
+    fn escape_round_trips() {
+        let text = "a\\b; c, d\nnext";
+        assert_eq!(unescape_text(&escape_text(text)), text);
+    }
+
+    #[test]
+    fn fold_keeps_lines_within_limit() {
+        let line = format!("DESCRIPTION:{}", "é".repeat(60));
+        let folded = fold_line(&line);
+        assert!(folded.split("\r\n").all(|l| l.len() <= ICS_LINE_LIMIT));
+
+        let events = parse_ics(&format!("BEGIN:VEVENT\r\n{}END:VEVENT\r\n", folded));
+        assert_eq!(events[0].description.as_deref(), Some("é".repeat(60).as_str()));
+    }
+
+    #[test]
+    fn export_round_trips_through_parse() {
+        let entry = TimeEntry {
+            id: 42,
+            project_id: 1,
+            project: Some(Project {
+                id: 1,
+                name: "Website".to_string(),
+                client: Some(Client { id: 2, name: "Acme".to_string(), hourly_rate: None }),
+            }),
+            description: Some("Fix header, footer".to_string()),
+            started_at: "2024-03-01T09:00:00.000Z".to_string(),
+            stopped_at: Some("2024-03-01T10:30:00.000Z".to_string()),
+            resumed_at: None,
+            duration_minutes: Some(90.0),
+            is_billable: true,
+        };
+        let events = parse_ics(&entries_to_ics(&[entry], at("2024-03-02T00:00:00Z")));
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].uid, "freshtracks-entry-42@freshtracks");
+        assert_eq!(events[0].summary, "Acme - Website");
+        assert_eq!(events[0].description.as_deref(), Some("Fix header, footer"));
+        assert_eq!(events[0].start, Some(at("2024-03-01T09:00:00Z")));
+        assert_eq!(events[0].end, Some(at("2024-03-01T10:30:00Z")));
+    }
+}
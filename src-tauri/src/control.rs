@@ -0,0 +1,75 @@
+// Timer actions shared by the local control interfaces (HTTP, JSON-RPC)
+
+use chrono::Utc;
+use serde::Serialize;
+use tauri::AppHandle;
+
+use crate::{
+    entry_elapsed_seconds, get_active_timer, get_time_entries, last_stopped_entry,
+    restart_timer_with_feedback, start_timer_with_feedback, stop_timer_with_feedback, PausedTimer,
+    TimeEntry, ACTIVE_TIMER_STATE, PAUSED_TIMER,
+};
+
+#[derive(Debug, Serialize)]
+pub struct TimerStatus {
+    pub running: Option<TimeEntry>,
+    pub elapsed_seconds: Option<i64>,
+    pub paused: Option<PausedTimer>,
+}
+
+// The server's running timer, falling back to cached state when offline. The cache
+// misses timers started or stopped elsewhere (e.g. the web app) until the next refresh.
+async fn running_timer() -> Option<TimeEntry> {
+    match get_active_timer().await {
+        Ok(timer) => timer,
+        Err(e) => {
+            eprintln!("Failed to fetch active timer, using cache: {}", e);
+            ACTIVE_TIMER_STATE.lock().unwrap().clone()
+        }
+    }
+}
+
+pub async fn status() -> TimerStatus {
+    let running = running_timer().await;
+    TimerStatus {
+        elapsed_seconds: running.as_ref().map(|e| entry_elapsed_seconds(e, Utc::now())),
+        running,
+        paused: PAUSED_TIMER.lock().unwrap().clone(),
+    }
+}
+
+// Start a timer, stopping the running one first (only one may run at a time)
+pub async fn start(app: &AppHandle, project_id: i32, description: Option<String>) -> Result<TimeEntry, String> {
+    if let Some(running) = running_timer().await {
+        stop_timer_with_feedback(app, running.id).await?;
+    }
+    let started = start_timer_with_feedback(app, project_id, description).await?;
+    // A paused entry can't be resumed once another timer has started
+    *PAUSED_TIMER.lock().unwrap() = None;
+    Ok(started)
+}
+
+// Stop the running timer; Ok(None) when nothing is running
+pub async fn stop(app: &AppHandle) -> Result<Option<TimeEntry>, String> {
+    match running_timer().await {
+        Some(running) => stop_timer_with_feedback(app, running.id).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+// Restart an entry, or the most recently stopped one
+pub async fn restart(app: &AppHandle, entry_id: Option<i32>) -> Result<TimeEntry, String> {
+    let entry_id = match entry_id {
+        Some(id) => id,
+        None => {
+            let entries = get_time_entries().await?;
+            last_stopped_entry(&entries)
+                .map(|e| e.id)
+                .ok_or_else(|| "No stopped entry to restart".to_string())?
+        }
+    };
+    if let Some(running) = running_timer().await.filter(|r| r.id != entry_id) {
+        stop_timer_with_feedback(app, running.id).await?;
+    }
+    restart_timer_with_feedback(app, entry_id).await
+}
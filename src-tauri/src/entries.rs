@@ -0,0 +1,580 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tauri::{AppHandle, Emitter};
+
+use crate::undo::{self, UndoAction};
+use crate::{
+    create_entry_internal, delete_entry_internal, entry_elapsed_seconds, fetch_time_entries_range, format_timestamp,
+    get_active_timer, get_time_entries, parse_timestamp, project_label, refresh_tray_menu_data, update_entry_internal,
+    TimeEntry, TimeEntryFilter, UpdateEntryData,
+};
+
+// Clock skew allowed before a stop time counts as "in the future"
+const FUTURE_TOLERANCE_SECS: i64 = 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NewEntryData {
+    pub project_id: i32,
+    pub description: Option<String>,
+    pub started_at: String,
+    pub stopped_at: String,
+    pub is_billable: bool,
+}
+
+// Interval an entry occupies; running entries extend to `now`
+pub fn entry_interval(entry: &TimeEntry, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = parse_timestamp(&entry.started_at)?;
+    let end = match entry.stopped_at.as_deref() {
+        Some(stopped) => parse_timestamp(stopped)?,
+        None => start + Duration::seconds(entry_elapsed_seconds(entry, now)),
+    };
+    Some((start, end))
+}
+
+// Entries (other than `ignore_id`) whose interval overlaps [start, end)
+pub fn overlapping(
+    entries: &[TimeEntry],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    ignore_id: Option<i32>,
+    now: DateTime<Utc>,
+) -> Vec<&TimeEntry> {
+    entries
+        .iter()
+        .filter(|e| Some(e.id) != ignore_id)
+        .filter(|e| entry_interval(e, now).is_some_and(|(s, f)| s < end && f > start))
+        .collect()
+}
+
+// Entries near an interval: the local days it covers plus the day before (for entries spanning midnight)
+pub async fn entries_around(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<Vec<TimeEntry>, String> {
+    let from = start.with_timezone(&Local).date_naive() - Duration::days(1);
+    let to = end.with_timezone(&Local).date_naive();
+    fetch_time_entries_range(&TimeEntryFilter {
+        from: Some(from.to_string()),
+        to: Some(to.to_string()),
+        ..Default::default()
+    }).await
+}
+
+// Check a finished interval against the clock and existing entries
+pub async fn check_new_interval(started_at: &str, stopped_at: &str, ignore_id: Option<i32>) -> Result<(), String> {
+    let start = parse_timestamp(started_at).ok_or_else(|| format!("Invalid started_at: {}", started_at))?;
+    let end = parse_timestamp(stopped_at).ok_or_else(|| format!("Invalid stopped_at: {}", stopped_at))?;
+    let now = Utc::now();
+
+    if end <= start {
+        return Err("The entry must stop after it starts".to_string());
+    }
+    if end > now + Duration::seconds(FUTURE_TOLERANCE_SECS) {
+        return Err("The entry can't end in the future".to_string());
+    }
+
+    let entries = entries_around(start, end).await?;
+    if let Some(other) = overlapping(&entries, start, end, ignore_id, now).first() {
+        let (other_start, other_end) = entry_interval(other, now).unwrap_or((start, end));
+        return Err(format!(
+            "Overlaps {} ({}–{})",
+            project_label(other.project.as_ref()),
+            other_start.with_timezone(&Local).format("%H:%M"),
+            other_end.with_timezone(&Local).format("%H:%M")
+        ));
+    }
+
+    Ok(())
+}
+
+// Log a finished entry by POSTing it with both times. A running timer is never touched:
+// if the server ignores the times and starts a live timer instead, that entry is deleted
+// again and this fails.
+pub async fn create_past_entry(
+    project_id: i32,
+    description: Option<String>,
+    started_at: String,
+    stopped_at: String,
+    is_billable: bool,
+) -> Result<TimeEntry, String> {
+    let created = create_entry_internal(project_id, description, started_at, Some(stopped_at), is_billable).await?;
+
+    if created.stopped_at.is_none() {
+        if let Err(e) = delete_entry_internal(created.id).await {
+            eprintln!("Failed to delete unexpected running entry {}: {}", created.id, e);
+        }
+        return Err("The server started a timer instead of logging a finished entry".to_string());
+    }
+
+    Ok(created)
+}
+
+// Log forgotten work after the fact; rejects future times and overlaps with existing entries
+#[tauri::command]
+pub async fn create_entry(app: AppHandle, data: NewEntryData) -> Result<TimeEntry, String> {
+    check_new_interval(&data.started_at, &data.stopped_at, None).await?;
+
+    // Normalise to the same timestamp format the rest of the app sends
+    let started_at = parse_timestamp(&data.started_at).map(format_timestamp).unwrap_or(data.started_at);
+    let stopped_at = parse_timestamp(&data.stopped_at).map(format_timestamp).unwrap_or(data.stopped_at);
+
+    let created = create_past_entry(data.project_id, data.description, started_at, stopped_at, data.is_billable).await?;
+
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(&app).await;
+
+    Ok(created)
+}
+
+pub fn restore_data(entry: &TimeEntry) -> UpdateEntryData {
+    UpdateEntryData {
+        project_id: entry.project_id,
+        description: entry.description.clone(),
+        started_at: entry.started_at.clone(),
+        stopped_at: entry.stopped_at.clone(),
+        is_billable: entry.is_billable,
+        resumed_at: None,
+    }
+}
+
+// Look entries up by id in the default listing, falling back to a full fetch
+pub async fn find_entries(ids: &[i32]) -> Result<Vec<TimeEntry>, String> {
+    let mut entries = get_time_entries().await?;
+    if !ids.iter().all(|id| entries.iter().any(|e| e.id == *id)) {
+        entries = fetch_time_entries_range(&TimeEntryFilter::default()).await?;
+    }
+
+    ids.iter()
+        .map(|id| {
+            entries
+                .iter()
+                .find(|e| e.id == *id)
+                .cloned()
+                .ok_or_else(|| format!("Entry {} was not found", id))
+        })
+        .collect()
+}
+
+// Start a running copy of `entry` from `started_at`. Fails instead of stopping another
+// timer if one is already running.
+pub async fn start_timer_at(entry: &TimeEntry, started_at: String) -> Result<TimeEntry, String> {
+    if let Some(running) = get_active_timer().await? {
+        return Err(format!(
+            "Stop the running timer ({}) first",
+            project_label(running.project.as_ref())
+        ));
+    }
+
+    create_entry_internal(entry.project_id, entry.description.clone(), started_at, None, entry.is_billable).await
+}
+
+// Where to split `entry`: strictly inside its interval. Resumed entries keep earlier
+// sessions in their banked duration, which neither half could carry.
+pub fn split_point(entry: &TimeEntry, at: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    if entry.resumed_at.is_some() {
+        return Err("Entries that were paused and resumed can't be split".to_string());
+    }
+    let split_at = parse_timestamp(at).ok_or_else(|| format!("Invalid split time: {}", at))?;
+    let (start, end) = entry_interval(entry, now).ok_or_else(|| "Entry has invalid times".to_string())?;
+    if split_at <= start || split_at >= end {
+        return Err("The split time must fall inside the entry".to_string());
+    }
+    Ok(split_at)
+}
+
+// Split an entry in two at `at`: the original ends there and a copy continues from it
+// (still running if the original was). The original is restored if creating the copy fails.
+#[tauri::command]
+pub async fn split_entry(app: AppHandle, entry_id: i32, at: String) -> Result<Vec<TimeEntry>, String> {
+    let original = find_entries(&[entry_id]).await?.remove(0);
+    let split_at = format_timestamp(split_point(&original, &at, Utc::now())?);
+
+    let first = update_entry_internal(original.id, UpdateEntryData {
+        stopped_at: Some(split_at.clone()),
+        ..restore_data(&original)
+    }).await?;
+
+    let second = match original.stopped_at.clone() {
+        Some(stopped_at) => {
+            create_past_entry(original.project_id, original.description.clone(), split_at, stopped_at, original.is_billable).await
+        }
+        // The second half keeps running from the split point
+        None => start_timer_at(&original, split_at).await,
+    };
+
+    let second = match second {
+        Ok(entry) => entry,
+        Err(e) => {
+            if let Err(restore_error) = update_entry_internal(original.id, restore_data(&original)).await {
+                eprintln!("Failed to restore entry {} after a failed split: {}", original.id, restore_error);
+            }
+            return Err(format!("Split failed: {}", e));
+        }
+    };
+
+    undo::record(&app, UndoAction::Split { original, created: second.clone() });
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(&app).await;
+
+    Ok(vec![first, second])
+}
+
+// Distinct non-empty descriptions in the order given, joined with "; "
+pub fn merged_description(entries: &[TimeEntry]) -> Option<String> {
+    let mut descriptions: Vec<&str> = Vec::new();
+    for description in entries.iter().filter_map(|e| e.description.as_deref()).map(str::trim) {
+        if !description.is_empty() && !descriptions.contains(&description) {
+            descriptions.push(description);
+        }
+    }
+    Some(descriptions.join("; ")).filter(|d| !d.is_empty())
+}
+
+// The merged entry is billable if the longest of the entries was
+pub fn merged_billable(entries: &[TimeEntry], now: DateTime<Utc>) -> bool {
+    entries
+        .iter()
+        .max_by_key(|e| entry_elapsed_seconds(e, now))
+        .map(|e| e.is_billable)
+        .unwrap_or(true)
+}
+
+// Span, description and billable flag of the entry that replaces `entries`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergePlan {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub description: Option<String>,
+    pub is_billable: bool,
+}
+
+// Plan a merge of stopped entries on one project, from the first start to the last stop.
+// Overlapping entries are fine; the merged span covers them all.
+pub fn plan_merge(entries: &[TimeEntry], now: DateTime<Utc>) -> Result<MergePlan, String> {
+    if entries.len() < 2 {
+        return Err("Select at least two entries to merge".to_string());
+    }
+    if entries.iter().any(|e| e.stopped_at.is_none()) {
+        return Err("Stop the running timer before merging it".to_string());
+    }
+    if entries.iter().any(|e| e.project_id != entries[0].project_id) {
+        return Err("Only entries on the same project can be merged".to_string());
+    }
+
+    let mut intervals = Vec::new();
+    for entry in entries {
+        intervals.push(entry_interval(entry, now).ok_or_else(|| format!("Entry {} has invalid times", entry.id))?);
+    }
+
+    let mut ordered = entries.to_vec();
+    ordered.sort_by_key(|e| parse_timestamp(&e.started_at));
+    Ok(MergePlan {
+        start: intervals.iter().map(|(s, _)| *s).min().unwrap_or(now),
+        end: intervals.iter().map(|(_, e)| *e).max().unwrap_or(now),
+        description: merged_description(&ordered),
+        is_billable: merged_billable(entries, now),
+    })
+}
+
+// Merge stopped entries on the same project into the earliest one (see plan_merge).
+// If deleting the others fails part-way, the survivor is restored and deleted entries
+// are recreated.
+#[tauri::command]
+pub async fn merge_entries(app: AppHandle, ids: Vec<i32>) -> Result<TimeEntry, String> {
+    let mut ids = ids;
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.len() < 2 {
+        return Err("Select at least two entries to merge".to_string());
+    }
+
+    let mut entries = find_entries(&ids).await?;
+    let now = Utc::now();
+    let plan = plan_merge(&entries, now)?;
+
+    // The merged span must not swallow entries that aren't part of the merge
+    let nearby = entries_around(plan.start, plan.end).await?;
+    if let Some(other) = overlapping(&nearby, plan.start, plan.end, None, now).into_iter().find(|e| !ids.contains(&e.id)) {
+        return Err(format!("The merged entry would overlap {}", project_label(other.project.as_ref())));
+    }
+
+    entries.sort_by_key(|e| parse_timestamp(&e.started_at));
+    let survivor = entries.remove(0);
+    let merged = update_entry_internal(survivor.id, UpdateEntryData {
+        project_id: survivor.project_id,
+        description: plan.description,
+        started_at: format_timestamp(plan.start),
+        stopped_at: Some(format_timestamp(plan.end)),
+        is_billable: plan.is_billable,
+        resumed_at: None,
+    }).await?;
+
+    let mut deleted: Vec<&TimeEntry> = Vec::new();
+    for entry in &entries {
+        if let Err(e) = delete_entry_internal(entry.id).await {
+            // Roll back: restore the survivor first so recreated entries don't overlap it
+            if let Err(restore_error) = update_entry_internal(survivor.id, restore_data(&survivor)).await {
+                eprintln!("Failed to restore entry {} after a failed merge: {}", survivor.id, restore_error);
+            }
+            for removed in &deleted {
+                let recreated = create_past_entry(
+                    removed.project_id,
+                    removed.description.clone(),
+                    removed.started_at.clone(),
+                    removed.stopped_at.clone().unwrap_or_default(),
+                    removed.is_billable,
+                ).await;
+                if let Err(recreate_error) = recreated {
+                    eprintln!("Failed to recreate entry {} after a failed merge: {}", removed.id, recreate_error);
+                }
+            }
+            let _ = app.emit("timer-toggled", ());
+            return Err(format!("Merge failed: {}", e));
+        }
+        deleted.push(entry);
+    }
+
+    undo::record(&app, UndoAction::Merged { survivor, removed: entries });
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(&app).await;
+
+    Ok(merged)
+}
+
+// Maximum bulk updates in flight at once
+const BULK_CONCURRENCY: usize = 4;
+
+// Changes applied to every selected entry; unset fields are left alone
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct BulkChanges {
+    pub project_id: Option<i32>,
+    pub is_billable: Option<bool>,
+    // Prepended to the description unless it already starts with it
+    pub description_prefix: Option<String>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BulkResult {
+    pub entry_id: i32,
+    pub entry: Option<TimeEntry>,
+    pub error: Option<String>,
+}
+
+pub fn apply_bulk_changes(entry: &TimeEntry, changes: &BulkChanges) -> UpdateEntryData {
+    let description = match changes.description_prefix.as_deref().filter(|p| !p.is_empty()) {
+        Some(prefix) => {
+            let current = entry.description.clone().unwrap_or_default();
+            if current.starts_with(prefix) {
+                Some(current)
+            } else if current.is_empty() {
+                Some(prefix.to_string())
+            } else {
+                Some(format!("{} {}", prefix.trim_end(), current))
+            }
+        }
+        None => entry.description.clone(),
+    };
+
+    UpdateEntryData {
+        project_id: changes.project_id.unwrap_or(entry.project_id),
+        description,
+        started_at: entry.started_at.clone(),
+        stopped_at: entry.stopped_at.clone(),
+        is_billable: changes.is_billable.unwrap_or(entry.is_billable),
+        resumed_at: None,
+    }
+}
+
+// Update several entries at once; failures are reported per entry instead of aborting
+#[tauri::command]
+pub async fn bulk_update_entries(app: AppHandle, ids: Vec<i32>, changes: BulkChanges) -> Result<Vec<BulkResult>, String> {
+    // Each entry is updated once, in the order it was first selected
+    let mut ids = ids;
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+
+    let mut entries = get_time_entries().await?;
+    if !ids.iter().all(|id| entries.iter().any(|e| e.id == *id)) {
+        entries = fetch_time_entries_range(&TimeEntryFilter::default()).await?;
+    }
+
+    let mut updates = Vec::new();
+    for &entry_id in &ids {
+        let data = entries
+            .iter()
+            .find(|e| e.id == entry_id)
+            .map(|entry| apply_bulk_changes(entry, &changes));
+        updates.push(async move {
+            let result = match data {
+                Some(data) => update_entry_internal(entry_id, data).await,
+                None => Err(format!("Entry {} was not found", entry_id)),
+            };
+            match result {
+                Ok(entry) => BulkResult { entry_id, entry: Some(entry), error: None },
+                Err(e) => BulkResult { entry_id, entry: None, error: Some(e) },
+            }
+        });
+    }
+
+    let before: Vec<TimeEntry> = entries.iter().filter(|e| ids.contains(&e.id)).cloned().collect();
+    let mut results: Vec<BulkResult> = futures_util::stream::iter(updates)
+        .buffer_unordered(BULK_CONCURRENCY)
+        .collect()
+        .await;
+    // Report in the order the entries were selected
+    results.sort_by_key(|r| ids.iter().position(|id| *id == r.entry_id));
+
+    // Only the entries that actually changed can be undone
+    let changed: Vec<TimeEntry> = before
+        .into_iter()
+        .filter(|e| results.iter().any(|r| r.entry_id == e.id && r.entry.is_some()))
+        .collect();
+    if !changed.is_empty() {
+        undo::record(&app, UndoAction::Updated { entries: changed });
+        let _ = app.emit("timer-toggled", ());
+        refresh_tray_menu_data(&app).await;
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        parse_timestamp(&format!("2024-06-05T{:02}:{:02}:00Z", hour, minute)).unwrap()
+    }
+
+    fn entry(id: i32, project_id: i32, start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> TimeEntry {
+        TimeEntry {
+            id,
+            project_id,
+            project: None,
+            description: None,
+            started_at: format_timestamp(start),
+            stopped_at: end.map(format_timestamp),
+            resumed_at: None,
+            duration_minutes: end.map(|end| (end - start).num_minutes() as f64),
+            is_billable: true,
+        }
+    }
+
+    fn described(mut entry: TimeEntry, description: &str, is_billable: bool) -> TimeEntry {
+        entry.description = Some(description.to_string());
+        entry.is_billable = is_billable;
+        entry
+    }
+
+    #[test]
+    fn split_inside_the_entry() {
+        let e = entry(1, 1, at(9, 0), Some(at(10, 0)));
+        assert_eq!(split_point(&e, &format_timestamp(at(9, 30)), at(12, 0)), Ok(at(9, 30)));
+    }
+
+    #[test]
+    fn split_at_a_boundary_is_rejected() {
+        let e = entry(1, 1, at(9, 0), Some(at(10, 0)));
+        assert!(split_point(&e, &format_timestamp(at(9, 0)), at(12, 0)).is_err());
+        assert!(split_point(&e, &format_timestamp(at(10, 0)), at(12, 0)).is_err());
+    }
+
+    #[test]
+    fn split_outside_the_range_is_rejected() {
+        let e = entry(1, 1, at(9, 0), Some(at(10, 0)));
+        assert!(split_point(&e, &format_timestamp(at(8, 0)), at(12, 0)).is_err());
+        assert!(split_point(&e, &format_timestamp(at(11, 0)), at(12, 0)).is_err());
+        assert!(split_point(&e, "not a time", at(12, 0)).is_err());
+    }
+
+    #[test]
+    fn running_entries_split_up_to_now() {
+        let e = entry(1, 1, at(9, 0), None);
+        assert_eq!(split_point(&e, &format_timestamp(at(9, 30)), at(10, 0)), Ok(at(9, 30)));
+        assert!(split_point(&e, &format_timestamp(at(10, 30)), at(10, 0)).is_err());
+    }
+
+    #[test]
+    fn resumed_entries_are_not_split() {
+        let mut e = entry(1, 1, at(9, 0), Some(at(10, 0)));
+        e.resumed_at = Some(format_timestamp(at(9, 45)));
+        assert!(split_point(&e, &format_timestamp(at(9, 50)), at(12, 0)).is_err());
+    }
+
+    #[test]
+    fn overlapping_merge_spans_all_entries() {
+        let entries = vec![
+            described(entry(2, 1, at(9, 30), Some(at(11, 0))), "Review", false),
+            described(entry(1, 1, at(9, 0), Some(at(10, 0))), "Design", true),
+            described(entry(3, 1, at(10, 30), Some(at(10, 45))), " Design ", true),
+        ];
+        let plan = plan_merge(&entries, at(12, 0)).unwrap();
+        assert_eq!(plan.start, at(9, 0));
+        assert_eq!(plan.end, at(11, 0));
+        // In start order, duplicates dropped
+        assert_eq!(plan.description.as_deref(), Some("Design; Review"));
+        // The longest entry (Review, 90 minutes) isn't billable
+        assert!(!plan.is_billable);
+    }
+
+    #[test]
+    fn merge_across_projects_is_rejected() {
+        let entries = vec![entry(1, 1, at(9, 0), Some(at(10, 0))), entry(2, 2, at(10, 0), Some(at(11, 0)))];
+        assert!(plan_merge(&entries, at(12, 0)).is_err());
+    }
+
+    #[test]
+    fn merge_with_a_running_entry_is_rejected() {
+        let entries = vec![entry(1, 1, at(9, 0), Some(at(10, 0))), entry(2, 1, at(10, 0), None)];
+        assert!(plan_merge(&entries, at(12, 0)).is_err());
+    }
+
+    #[test]
+    fn bulk_changes_keep_unset_fields() {
+        let e = described(entry(1, 3, at(9, 0), Some(at(10, 0))), "Design", false);
+        let data = apply_bulk_changes(&e, &BulkChanges::default());
+        assert_eq!(data.project_id, 3);
+        assert_eq!(data.description.as_deref(), Some("Design"));
+        assert_eq!(data.started_at, e.started_at);
+        assert_eq!(data.stopped_at, e.stopped_at);
+        assert!(!data.is_billable);
+    }
+
+    #[test]
+    fn bulk_changes_toggle_billable() {
+        let e = described(entry(1, 3, at(9, 0), Some(at(10, 0))), "Design", false);
+        let changes = BulkChanges { is_billable: Some(true), ..Default::default() };
+        let data = apply_bulk_changes(&e, &changes);
+        assert!(data.is_billable);
+        assert_eq!(data.project_id, 3);
+    }
+
+    #[test]
+    fn bulk_changes_override_project() {
+        let e = described(entry(1, 3, at(9, 0), Some(at(10, 0))), "Design", true);
+        let changes = BulkChanges { project_id: Some(7), ..Default::default() };
+        let data = apply_bulk_changes(&e, &changes);
+        assert_eq!(data.project_id, 7);
+        assert_eq!(data.description.as_deref(), Some("Design"));
+        assert!(data.is_billable);
+    }
+
+    #[test]
+    fn bulk_prefix_is_added_once() {
+        let e = described(entry(1, 3, at(9, 0), Some(at(10, 0))), "Design", true);
+        let changes = BulkChanges { description_prefix: Some("[ACME] ".to_string()), ..Default::default() };
+        let data = apply_bulk_changes(&e, &changes);
+        assert_eq!(data.description.as_deref(), Some("[ACME] Design"));
+
+        let again = described(e, "[ACME] Design", true);
+        assert_eq!(apply_bulk_changes(&again, &changes).description.as_deref(), Some("[ACME] Design"));
+    }
+
+    #[test]
+    fn merged_description_skips_blanks() {
+        let entries = vec![
+            described(entry(1, 1, at(9, 0), Some(at(10, 0))), "  ", true),
+            entry(2, 1, at(10, 0), Some(at(11, 0))),
+        ];
+        assert_eq!(merged_description(&entries), None);
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::Local;
+use freshtracks::export::{render_with_rounding, ExportFormat};
+use tauri::AppHandle;
+use tauri_plugin_dialog::DialogExt;
+
+use crate::preferences;
+use crate::TimeEntryFilter;
+
+// Render entries matching the filter in the given format
+async fn render_export(filter: &TimeEntryFilter, format: ExportFormat) -> Result<String, String> {
+    render_with_rounding(filter, format, preferences::get().reports.rounding).await
+}
+
+// Export entries and return the content (for previews or copying)
+#[tauri::command]
+pub async fn export_entries(filter: TimeEntryFilter, format: ExportFormat) -> Result<String, String> {
+    render_export(&filter, format).await
+}
+
+// Export entries to a file picked in a save dialog; returns the path, or None if cancelled
+#[tauri::command]
+pub async fn export_entries_to_file(app: AppHandle, filter: TimeEntryFilter, format: ExportFormat) -> Result<Option<String>, String> {
+    let content = render_export(&filter, format).await?;
+
+    let range = match (&filter.from, &filter.to) {
+        (Some(from), Some(to)) => format!("{}_{}", from, to),
+        (Some(from), None) => from.clone(),
+        _ => Local::now().date_naive().to_string(),
+    };
+    let kind = if format == ExportFormat::Invoice { "invoice" } else { "timesheet" };
+    let file_name = format!("freshtracks-{}-{}.{}", kind, range, format.extension());
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .file()
+        .set_file_name(&file_name)
+        .add_filter(format.extension().to_uppercase(), &[format.extension()])
+        .save_file(move |path| {
+            let _ = tx.send(path);
+        });
+
+    let Some(path) = rx.await.map_err(|e| e.to_string())? else {
+        return Ok(None);
+    };
+    let path = path.into_path().map_err(|e| e.to_string())?;
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    println!("Exported entries to {}", path.display());
+    Ok(Some(path.display().to_string()))
+}
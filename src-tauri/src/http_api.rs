@@ -0,0 +1,228 @@
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Mutex;
+use tauri::async_runtime::JoinHandle;
+use tauri::AppHandle;
+use tokio::net::TcpListener;
+
+use crate::control;
+use crate::preferences;
+use crate::search;
+
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct HttpApiSettings {
+    // Off unless the user opts in
+    pub enabled: bool,
+    pub port: u16,
+    // Required as "Authorization: Bearer <token>"; generated when empty
+    pub token: String,
+}
+
+impl Default for HttpApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7823,
+            token: String::new(),
+        }
+    }
+}
+
+// Running server task, replaced whenever the settings change
+static SERVER: Lazy<Mutex<Option<JoinHandle<()>>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Deserialize)]
+struct StartRequest {
+    project_id: i32,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RestartRequest {
+    // Defaults to the most recently stopped entry
+    #[serde(default)]
+    entry_id: Option<i32>,
+}
+
+// 32 random bytes as hex
+pub fn generate_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::getrandom(&mut bytes).map_err(|e| format!("Failed to generate token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Compare without returning early on the first differing byte
+fn token_matches(expected: &str, given: &str) -> bool {
+    if expected.is_empty() || expected.len() != given.len() {
+        return false;
+    }
+    expected
+        .bytes()
+        .zip(given.bytes())
+        .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+        == 0
+}
+
+fn json_response<T: Serialize>(status: StatusCode, body: &T) -> Response<Full<Bytes>> {
+    let body = serde_json::to_vec(body).unwrap_or_default();
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap()
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Full<Bytes>> {
+    json_response(status, &serde_json::json!({ "error": message }))
+}
+
+// Parse a JSON body; an empty body yields `empty` when one is given
+async fn read_json<T: for<'de> Deserialize<'de>>(req: Request<Incoming>, empty: Option<T>) -> Result<T, String> {
+    let bytes = Limited::new(req.into_body(), MAX_BODY_BYTES)
+        .collect()
+        .await
+        .map_err(|e| format!("Failed to read body: {}", e))?
+        .to_bytes();
+    if bytes.iter().all(u8::is_ascii_whitespace) {
+        if let Some(value) = empty {
+            return Ok(value);
+        }
+    }
+    serde_json::from_slice(&bytes).map_err(|e| format!("Invalid JSON body: {}", e))
+}
+
+async fn route(app: AppHandle, req: Request<Incoming>) -> Response<Full<Bytes>> {
+    let token = preferences::get().http_api.token;
+    let authorized = req
+        .headers()
+        .get("Authorization")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|given| token_matches(&token, given.trim()));
+    if !authorized {
+        return error_response(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token");
+    }
+
+    let method = req.method().clone();
+    let path = req.uri().path().trim_end_matches('/').to_string();
+
+    match (method, path.as_str()) {
+        (Method::GET, "/status") => json_response(StatusCode::OK, &control::status().await),
+        (Method::GET, "/projects") => json_response(StatusCode::OK, &search::cached_projects().await),
+        (Method::POST, "/start") => match read_json::<StartRequest>(req, None).await {
+            Ok(body) => match control::start(&app, body.project_id, body.description).await {
+                Ok(entry) => json_response(StatusCode::OK, &entry),
+                Err(e) => error_response(StatusCode::BAD_GATEWAY, &e),
+            },
+            Err(e) => error_response(StatusCode::BAD_REQUEST, &e),
+        },
+        (Method::POST, "/stop") => match control::stop(&app).await {
+            Ok(Some(entry)) => json_response(StatusCode::OK, &entry),
+            Ok(None) => error_response(StatusCode::CONFLICT, "No timer is running"),
+            Err(e) => error_response(StatusCode::BAD_GATEWAY, &e),
+        },
+        (Method::POST, "/restart") => match read_json::<RestartRequest>(req, Some(RestartRequest::default())).await {
+            Ok(body) => match control::restart(&app, body.entry_id).await {
+                Ok(entry) => json_response(StatusCode::OK, &entry),
+                Err(e) => error_response(StatusCode::BAD_GATEWAY, &e),
+            },
+            Err(e) => error_response(StatusCode::BAD_REQUEST, &e),
+        },
+        (_, "/status" | "/projects" | "/start" | "/stop" | "/restart") => {
+            error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed")
+        }
+        _ => error_response(StatusCode::NOT_FOUND, "Not found"),
+    }
+}
+
+async fn serve(app: AppHandle, port: u16) {
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("Failed to start HTTP control API on {}: {}", addr, e);
+            return;
+        }
+    };
+    println!("HTTP control API listening on http://{}", addr);
+
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                eprintln!("HTTP control API accept failed: {}", e);
+                continue;
+            }
+        };
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            let service = service_fn(move |req| {
+                let app = app.clone();
+                async move { Ok::<_, Infallible>(route(app, req).await) }
+            });
+            if let Err(e) = http1::Builder::new().serve_connection(TokioIo::new(stream), service).await {
+                eprintln!("HTTP control API connection error: {}", e);
+            }
+        });
+    }
+}
+
+// (Re)start the server from the current settings, stopping any previous one
+pub fn restart_server(app: &AppHandle) {
+    if let Some(handle) = SERVER.lock().unwrap().take() {
+        handle.abort();
+    }
+
+    let settings = preferences::get().http_api;
+    if !settings.enabled {
+        return;
+    }
+    if settings.token.is_empty() {
+        eprintln!("HTTP control API is enabled without a token; not starting");
+        return;
+    }
+
+    let app_handle = app.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        serve(app_handle, settings.port).await;
+    });
+    *SERVER.lock().unwrap() = Some(handle);
+}
+
+// Save settings (generating a token on first enable) and apply them
+#[tauri::command]
+pub async fn set_http_api_settings(app: AppHandle, settings: HttpApiSettings) -> Result<HttpApiSettings, String> {
+    let mut settings = settings;
+    if settings.token.trim().is_empty() {
+        settings.token = generate_token()?;
+    }
+    if settings.port == 0 {
+        return Err("Port must be between 1 and 65535".to_string());
+    }
+
+    let prefs = preferences::update(|prefs| prefs.http_api = settings)?;
+    restart_server(&app);
+    Ok(prefs.http_api)
+}
+
+// Replace the token; clients using the old one are rejected immediately
+#[tauri::command]
+pub async fn regenerate_http_api_token(app: AppHandle) -> Result<HttpApiSettings, String> {
+    let token = generate_token()?;
+    let prefs = preferences::update(|prefs| prefs.http_api.token = token)?;
+    restart_server(&app);
+    Ok(prefs.http_api)
+}
@@ -0,0 +1,362 @@
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::notifications::{self, NotificationAction, NotificationCategory};
+use crate::power;
+use crate::preferences;
+use crate::{
+    create_entry_internal, format_timestamp, refresh_tray_menu_data, show_main_window,
+    update_entry_internal, TimeEntry, UpdateEntryData, ACTIVE_TIMER_STATE,
+};
+
+// How often the idle monitor samples user activity
+const POLL_INTERVAL_SECS: u64 = 15;
+
+// Source of "seconds since last user input", implemented per platform
+pub trait IdleSource: Send + Sync {
+    // None when idle time can't be determined on this platform
+    fn idle_seconds(&self) -> Option<u64>;
+}
+
+// macOS: ask Quartz for the time since any input event in the login session
+#[cfg(target_os = "macos")]
+pub struct MacIdleSource;
+
+#[cfg(target_os = "macos")]
+#[link(name = "CoreGraphics", kind = "framework")]
+extern "C" {
+    fn CGEventSourceSecondsSinceLastEventType(source_state_id: i32, event_type: u32) -> f64;
+}
+
+#[cfg(target_os = "macos")]
+impl IdleSource for MacIdleSource {
+    fn idle_seconds(&self) -> Option<u64> {
+        // kCGEventSourceStateCombinedSessionState = 0, kCGAnyInputEventType = ~0
+        let secs = unsafe { CGEventSourceSecondsSinceLastEventType(0, u32::MAX) };
+        if secs.is_finite() && secs >= 0.0 {
+            Some(secs as u64)
+        } else {
+            None
+        }
+    }
+}
+
+// Platforms without idle detection never report idle time
+#[cfg(not(target_os = "macos"))]
+pub struct UnsupportedIdleSource;
+
+#[cfg(not(target_os = "macos"))]
+impl IdleSource for UnsupportedIdleSource {
+    fn idle_seconds(&self) -> Option<u64> {
+        None
+    }
+}
+
+fn platform_idle_source() -> Box<dyn IdleSource> {
+    #[cfg(target_os = "macos")]
+    return Box::new(MacIdleSource);
+    #[cfg(not(target_os = "macos"))]
+    return Box::new(UnsupportedIdleSource);
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct IdleSettings {
+    pub enabled: bool,
+    pub threshold_minutes: u32,
+}
+
+impl Default for IdleSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_minutes: 10,
+        }
+    }
+}
+
+// Idle period detected while a timer was running, waiting for the user's decision
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct IdlePrompt {
+    pub entry: TimeEntry,
+    pub idle_started_at: String,
+    pub returned_at: String,
+    pub idle_minutes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IdleAction {
+    // Count the idle time as work
+    Keep,
+    // End the entry when idling began and start a fresh one from when the user returned
+    Discard,
+    // End the entry when idling began and move the idle time into a new entry
+    Split,
+}
+
+// Pure idle state machine, fed one sample per poll
+#[derive(Debug, Default)]
+pub struct IdleTracker {
+    idle_since: Option<DateTime<Utc>>,
+}
+
+impl IdleTracker {
+    // Returns the idle start time once the user comes back from an idle period
+    pub fn observe(&mut self, idle_secs: u64, threshold_secs: u64, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if idle_secs >= threshold_secs {
+            if self.idle_since.is_none() {
+                self.idle_since = Some(now - Duration::seconds(idle_secs as i64));
+            }
+            return None;
+        }
+
+        // Below the threshold again after idling means the user is back
+        self.idle_since.take()
+    }
+
+    pub fn reset(&mut self) {
+        self.idle_since = None;
+    }
+}
+
+static PENDING_PROMPT: Lazy<Mutex<Option<IdlePrompt>>> = Lazy::new(|| Mutex::new(None));
+
+// Poll the platform idle source and prompt when the user returns from idling with a timer running
+pub async fn run_idle_monitor(app: AppHandle) {
+    let source = platform_idle_source();
+    let mut tracker = IdleTracker::default();
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(POLL_INTERVAL_SECS)).await;
+
+        let settings = preferences::get().idle;
+        let timer = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+        let (Some(timer), true) = (timer, settings.enabled) else {
+            tracker.reset();
+            continue;
+        };
+        let Some(idle_secs) = source.idle_seconds() else {
+            continue;
+        };
+
+        let now = Utc::now();
+        let threshold_secs = settings.threshold_minutes as u64 * 60;
+        if let Some(idle_since) = tracker.observe(idle_secs, threshold_secs, now) {
+            let prompt = IdlePrompt {
+                entry: timer,
+                idle_started_at: format_timestamp(idle_since),
+                returned_at: format_timestamp(now),
+                idle_minutes: (now - idle_since).num_minutes(),
+            };
+            prompt_user(&app, prompt);
+        }
+    }
+}
+
+fn prompt_user(app: &AppHandle, prompt: IdlePrompt) {
+    println!("User returned after {} idle minutes", prompt.idle_minutes);
+    // The idle period covers any sleep or lock in it; answering both would subtract it twice
+    power::clear_pending_prompt();
+    *PENDING_PROMPT.lock().unwrap() = Some(prompt.clone());
+
+    notifications::notify(
+        app,
+        NotificationCategory::Idle,
+        "You were idle",
+        &format!("Idle for {}m while the timer was running. Keep, discard or split it?", prompt.idle_minutes),
+        &[NotificationAction::Open],
+    );
+
+    show_main_window(app);
+    let _ = app.emit("idle-detected", prompt);
+}
+
+// Forget the idle period once an away prompt covers it
+pub fn clear_pending_prompt() {
+    *PENDING_PROMPT.lock().unwrap() = None;
+}
+
+#[tauri::command]
+pub async fn get_idle_prompt() -> Result<Option<IdlePrompt>, String> {
+    Ok(PENDING_PROMPT.lock().unwrap().clone())
+}
+
+// Where the running entry ends and where the next one starts for an action;
+// None leaves the entry untouched
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdleResolution {
+    pub stopped_at: String,
+    pub next_started_at: String,
+}
+
+pub fn plan_resolution(prompt: &IdlePrompt, action: IdleAction) -> Option<IdleResolution> {
+    match action {
+        IdleAction::Keep => None,
+        IdleAction::Discard => Some(IdleResolution {
+            stopped_at: prompt.idle_started_at.clone(),
+            next_started_at: prompt.returned_at.clone(),
+        }),
+        IdleAction::Split => Some(IdleResolution {
+            stopped_at: prompt.idle_started_at.clone(),
+            next_started_at: prompt.idle_started_at.clone(),
+        }),
+    }
+}
+
+// Apply the user's choice for the pending idle period
+#[tauri::command]
+pub async fn resolve_idle(app: AppHandle, action: IdleAction) -> Result<Option<TimeEntry>, String> {
+    // Stays pending until the change is applied so a failed attempt can be retried
+    let prompt = PENDING_PROMPT
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "No idle period is pending".to_string())?;
+
+    let Some(resolution) = plan_resolution(&prompt, action) else {
+        *PENDING_PROMPT.lock().unwrap() = None;
+        return Ok(None);
+    };
+
+    let result = apply_resolution(&prompt.entry, &resolution).await;
+    if result.is_ok() {
+        *PENDING_PROMPT.lock().unwrap() = None;
+    }
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(&app).await;
+
+    result.map(Some)
+}
+
+async fn apply_resolution(entry: &TimeEntry, resolution: &IdleResolution) -> Result<TimeEntry, String> {
+    // End the running entry where the idle period began
+    update_entry_internal(entry.id, UpdateEntryData {
+        project_id: entry.project_id,
+        description: entry.description.clone(),
+        started_at: entry.started_at.clone(),
+        stopped_at: Some(resolution.stopped_at.clone()),
+        is_billable: entry.is_billable,
+        resumed_at: None,
+    }).await?;
+
+    // Continue on the same project with a new entry
+    let started = create_entry_internal(
+        entry.project_id,
+        entry.description.clone(),
+        resolution.next_started_at.clone(),
+        None,
+        entry.is_billable,
+    ).await?;
+
+    *ACTIVE_TIMER_STATE.lock().unwrap() = Some(started.clone());
+    Ok(started)
+}
+
+#[tauri::command]
+pub async fn set_idle_settings(settings: IdleSettings) -> Result<IdleSettings, String> {
+    let prefs = preferences::update(|prefs| prefs.idle = settings)?;
+    Ok(prefs.idle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    // Replays scripted idle samples, one per poll
+    struct FakeIdleSource {
+        samples: Mutex<Vec<Option<u64>>>,
+    }
+
+    impl FakeIdleSource {
+        fn new(samples: &[Option<u64>]) -> Self {
+            Self { samples: Mutex::new(samples.iter().rev().copied().collect()) }
+        }
+    }
+
+    impl IdleSource for FakeIdleSource {
+        fn idle_seconds(&self) -> Option<u64> {
+            self.samples.lock().unwrap().pop().flatten()
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap()
+    }
+
+    // Poll the source every POLL_INTERVAL_SECS from `start`, collecting idle starts
+    fn run(source: &dyn IdleSource, polls: usize, threshold_secs: u64, start: DateTime<Utc>) -> Vec<DateTime<Utc>> {
+        let mut tracker = IdleTracker::default();
+        (0..polls)
+            .filter_map(|i| {
+                let now = start + Duration::seconds((i as u64 * POLL_INTERVAL_SECS) as i64);
+                let idle_secs = source.idle_seconds()?;
+                tracker.observe(idle_secs, threshold_secs, now)
+            })
+            .collect()
+    }
+
+    fn prompt() -> IdlePrompt {
+        IdlePrompt {
+            entry: TimeEntry {
+                id: 7,
+                project_id: 3,
+                project: None,
+                description: Some("Review".to_string()),
+                started_at: format_timestamp(at(9, 0)),
+                stopped_at: None,
+                resumed_at: None,
+                duration_minutes: None,
+                is_billable: true,
+            },
+            idle_started_at: format_timestamp(at(10, 0)),
+            returned_at: format_timestamp(at(10, 20)),
+            idle_minutes: 20,
+        }
+    }
+
+    #[test]
+    fn reports_idle_start_once_user_returns() {
+        let source = FakeIdleSource::new(&[Some(0), Some(600), Some(615), Some(630), Some(2)]);
+        let returned = run(&source, 5, 600, at(10, 10));
+        // First over-threshold sample came at 10:10:15 with 600s idle
+        assert_eq!(returned, vec![at(10, 0) + Duration::seconds(15)]);
+    }
+
+    #[test]
+    fn ignores_short_idle_and_unknown_samples() {
+        let source = FakeIdleSource::new(&[Some(30), None, Some(599), None, Some(1)]);
+        assert!(run(&source, 5, 600, at(10, 0)).is_empty());
+    }
+
+    #[test]
+    fn reset_forgets_idle_period() {
+        let mut tracker = IdleTracker::default();
+        assert_eq!(tracker.observe(900, 600, at(10, 15)), None);
+        tracker.reset();
+        assert_eq!(tracker.observe(0, 600, at(10, 16)), None);
+    }
+
+    #[test]
+    fn keep_leaves_entry_untouched() {
+        assert_eq!(plan_resolution(&prompt(), IdleAction::Keep), None);
+    }
+
+    #[test]
+    fn discard_drops_idle_time() {
+        let plan = plan_resolution(&prompt(), IdleAction::Discard).unwrap();
+        assert_eq!(plan.stopped_at, format_timestamp(at(10, 0)));
+        assert_eq!(plan.next_started_at, format_timestamp(at(10, 20)));
+    }
+
+    #[test]
+    fn split_moves_idle_time_into_new_entry() {
+        let plan = plan_resolution(&prompt(), IdleAction::Split).unwrap();
+        assert_eq!(plan.stopped_at, format_timestamp(at(10, 0)));
+        assert_eq!(plan.next_started_at, format_timestamp(at(10, 0)));
+    }
+}
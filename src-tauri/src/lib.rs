@@ -1,17 +1,51 @@
 use tauri::{
     image::Image,
-    menu::{Menu, MenuItem},
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent, TrayIcon},
-    Manager, PhysicalPosition, Emitter, AppHandle,
+    Manager, PhysicalPosition, Emitter, AppHandle, Wry,
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Mutex;
 use std::io::Cursor;
 use once_cell::sync::Lazy;
-use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut};
-use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_shell::ShellExt;
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use futures_util::{StreamExt, SinkExt};
+use chrono::{DateTime, Local, Utc};
+
+// Widget API types and requests, shared with the freshtracks CLI
+use freshtracks::{
+    create_entry as create_entry_internal, delete_entry as delete_entry_internal,
+    entry_elapsed_seconds, fetch_time_entries_range, format_timer_text, format_timestamp,
+    last_stopped_entry, parse_timestamp, project_label, start_timer as start_timer_internal,
+    stop_timer as stop_timer_internal, update_entry as update_entry_internal, Client, Project,
+    TimeEntry, TimeEntryFilter, UpdateEntryData, UserSettings,
+};
+
+mod calendar;
+mod control;
+mod entries;
+mod export;
+mod http_api;
+mod idle;
+mod notifications;
+mod palette;
+mod pomodoro;
+mod power;
+mod preferences;
+mod quick_start;
+mod reminders;
+mod reports;
+#[cfg(unix)]
+mod rpc;
+mod search;
+mod shortcuts;
+mod suggestions;
+mod templates;
+mod undo;
+mod validation;
+
+use notifications::{NotificationAction, NotificationCategory};
 
 // Image rendering imports
 use image::{ImageBuffer, Rgba, RgbaImage};
@@ -23,87 +57,34 @@ static TRAY_ICON: Lazy<Mutex<Option<TrayIcon>>> = Lazy::new(|| Mutex::new(None))
 // Store active timer state for shortcut toggle
 static ACTIVE_TIMER_STATE: Lazy<Mutex<Option<TimeEntry>>> = Lazy::new(|| Mutex::new(None));
 
-// Store first project ID for quick-start via shortcut
+// Timer paused from the widget (stopped on the server, resumed via restart)
+static PAUSED_TIMER: Lazy<Mutex<Option<PausedTimer>>> = Lazy::new(|| Mutex::new(None));
+
+// Store first project ID as the last-resort quick-start target (see quick_start)
 static FIRST_PROJECT_ID: Lazy<Mutex<Option<i32>>> = Lazy::new(|| Mutex::new(None));
 
 // Cache the last rendered timer text to avoid flickering on redundant updates
 static LAST_TIMER_TEXT: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
 
-// API types
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct Client {
-    #[serde(default)]
-    pub id: i32,
-    #[serde(default)]
-    pub name: String,
-    #[serde(default, deserialize_with = "deserialize_optional_f64")]
-    pub hourly_rate: Option<f64>,
-}
-
-// Helper to deserialize hourly_rate which might be string, number, or null
-fn deserialize_optional_f64<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
-where
-    D: serde::Deserializer<'de>,
-{
-    let value: Option<serde_json::Value> = Option::deserialize(deserializer)?;
-    match value {
-        None => Ok(None),
-        Some(serde_json::Value::Null) => Ok(None),
-        Some(serde_json::Value::Number(n)) => Ok(n.as_f64()),
-        Some(serde_json::Value::String(s)) => {
-            let trimmed = s.trim();
-            if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("null") {
-                Ok(None)
-            } else {
-                // Be lenient - if it's not a valid number, just return None
-                Ok(trimmed.parse::<f64>().ok())
-            }
-        }
-        Some(_) => Ok(None),
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Default)]
-pub struct UserSettings {
-    pub show_live_revenue: Option<bool>,
-}
+// Cached data used to build the tray menu without hitting the API on every rebuild
+static RECENT_PROJECTS: Lazy<Mutex<Vec<Project>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static RECENT_ENTRIES: Lazy<Mutex<Vec<TimeEntry>>> = Lazy::new(|| Mutex::new(Vec::new()));
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Project {
-    pub id: i32,
-    pub name: String,
-    pub client: Option<Client>,
-}
+// Signalled to drop and re-open the WebSocket (e.g. after waking from sleep)
+static WS_RECONNECT: Lazy<tokio::sync::Notify> = Lazy::new(tokio::sync::Notify::new);
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct TimeEntry {
-    pub id: i32,
-    pub project_id: i32,
-    pub project: Option<Project>,
-    pub description: Option<String>,
-    pub started_at: String,
-    pub stopped_at: Option<String>,
-    pub resumed_at: Option<String>,
-    pub duration_minutes: Option<f64>,
-    pub is_billable: bool,
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct ApiResponse<T> {
-    pub data: T,
-}
+// Whether the WebSocket is currently connected (shown as sync status in the tray menu)
+static SYNC_CONNECTED: Lazy<Mutex<bool>> = Lazy::new(|| Mutex::new(false));
 
+// A paused entry and its elapsed time frozen at the moment it was paused
 #[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct UpdateEntryData {
-    pub project_id: i32,
-    pub description: Option<String>,
-    pub started_at: String,
-    pub stopped_at: Option<String>,
-    pub is_billable: bool,
+pub struct PausedTimer {
+    pub entry: TimeEntry,
+    pub elapsed_seconds: i64,
+    pub paused_at: String,
 }
 
-const API_BASE: &str = "http://localhost:8000/api/widget";
-const WIDGET_SECRET: &str = "freshtracks-widget-dev-secret";
+const WEB_APP_URL: &str = "http://localhost:3000";
 
 // WebSocket configuration for Laravel Reverb
 const REVERB_HOST: &str = "localhost";
@@ -111,10 +92,6 @@ const REVERB_PORT: u16 = 8080;
 const REVERB_APP_KEY: &str = "u2oi0cwsi7cmbmnkteku";
 const WIDGET_USER_ID: i32 = 1; // Same as Laravel widget user
 
-fn get_http_client() -> reqwest::Client {
-    reqwest::Client::new()
-}
-
 // WebSocket connection for real-time sync
 async fn connect_websocket(app: AppHandle) {
     let ws_url = format!("ws://{}:{}/app/{}?protocol=7&client=rust&version=1.0",
@@ -126,6 +103,7 @@ async fn connect_websocket(app: AppHandle) {
         match connect_async(&ws_url).await {
             Ok((ws_stream, _)) => {
                 println!("WebSocket connected successfully");
+                set_sync_connected(&app, true);
                 let (mut write, mut read) = ws_stream.split();
 
                 // Subscribe to the timers channel for this user (Pusher protocol)
@@ -142,8 +120,19 @@ async fn connect_websocket(app: AppHandle) {
                 }
                 println!("Subscribed to timers.{} channel", WIDGET_USER_ID);
 
-                // Process incoming messages
-                while let Some(msg) = read.next().await {
+                // Process incoming messages until the socket closes or a reconnect is requested
+                loop {
+                    let msg = tokio::select! {
+                        msg = read.next() => msg,
+                        _ = WS_RECONNECT.notified() => {
+                            println!("WebSocket reconnect requested");
+                            break;
+                        }
+                    };
+                    let Some(msg) = msg else {
+                        break;
+                    };
+
                     match msg {
                         Ok(Message::Text(text)) => {
                             handle_websocket_message(&app, &text);
@@ -169,16 +158,38 @@ async fn connect_websocket(app: AppHandle) {
             }
         }
 
+        set_sync_connected(&app, false);
+
         // Wait before reconnecting
         println!("Reconnecting in 5 seconds...");
         tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
     }
 }
 
+// Update sync status and rebuild the tray menu if it changed
+fn set_sync_connected(app: &AppHandle, connected: bool) {
+    {
+        let mut state = SYNC_CONNECTED.lock().unwrap();
+        if *state == connected {
+            return;
+        }
+        *state = connected;
+    }
+    rebuild_tray_menu(app);
+}
+
 // Handle incoming WebSocket messages (Pusher protocol)
 fn handle_websocket_message(app: &AppHandle, text: &str) {
     if let Ok(data) = serde_json::from_str::<serde_json::Value>(text) {
         if let Some(event) = data.get("event").and_then(|e| e.as_str()) {
+            // Any timer change may affect the tray menu (active timer, today's total)
+            if event.starts_with("timer.") {
+                let app_clone = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    refresh_tray_menu_data(&app_clone).await;
+                });
+            }
+
             match event {
                 "timer.started" => {
                     println!("Received timer.started event");
@@ -290,7 +301,8 @@ fn is_inside_rounded_rect(x: u32, y: u32, width: u32, height: u32, radius: u32)
 
 // Render a combined image with icon + timer text for the menu bar
 // This gives us full control over font size and positioning (unlike set_title)
-fn render_tray_image(timer_text: Option<&str>) -> Vec<u8> {
+// Paused timers and pomodoro breaks render their text in grey
+fn render_tray_image(timer_text: Option<&str>, paused: bool) -> Vec<u8> {
     // Load the embedded font
     let font = Font::try_from_bytes(FONT_DATA).expect("Failed to load embedded font");
 
@@ -385,7 +397,11 @@ fn render_tray_image(timer_text: Option<&str>) -> Vec<u8> {
         let text_x = h_padding + 34 + 14;
 
         // Render in black - we won't use template mode to avoid flashing
-        let text_color = Rgba([0, 0, 0, 255]); // Black for non-template mode
+        let text_color = if paused {
+            Rgba([120, 120, 120, 255]) // Grey for a frozen (paused) timer
+        } else {
+            Rgba([0, 0, 0, 255]) // Black for non-template mode
+        };
 
         for glyph in font.layout(text, scale, rusttype::point(text_x as f32, baseline_y)) {
             if let Some(bounding_box) = glyph.pixel_bounding_box() {
@@ -425,6 +441,11 @@ fn render_tray_image(timer_text: Option<&str>) -> Vec<u8> {
 // Update tray tooltip and icon based on timer state
 #[tauri::command]
 async fn update_tray_status(_app: tauri::AppHandle, is_running: bool, project_name: Option<String>) -> Result<(), String> {
+    // The pomodoro countdown owns the tray while a cycle runs
+    if pomodoro::is_active() {
+        return Ok(());
+    }
+
     if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
         // Update tooltip
         let tooltip = if is_running {
@@ -444,29 +465,49 @@ async fn update_tray_status(_app: tauri::AppHandle, is_running: bool, project_na
 
         // When not running, reset to the default rendered icon (black, no template)
         if !is_running {
-            let icon_data = render_tray_image(None);
+            let icon_data = render_tray_image(None, false);
             if let Ok(icon) = Image::from_bytes(&icon_data) {
                 let _ = tray.set_icon(Some(icon));
             }
         }
         // When running, the icon is handled by update_tray_timer
     }
+
+    // A paused timer keeps its frozen time in the tray instead of the idle icon
+    if !is_running {
+        show_paused_in_tray();
+    }
     Ok(())
 }
 
+// Render the paused timer (if any) in the tray with its frozen elapsed time
+fn show_paused_in_tray() {
+    let Some(paused) = PAUSED_TIMER.lock().unwrap().clone() else {
+        return;
+    };
+    *LAST_TIMER_TEXT.lock().unwrap() = None;
+
+    if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
+        let tooltip = format!("FreshTracks - {} (Paused)", project_label(paused.entry.project.as_ref()));
+        let _ = tray.set_tooltip(Some(&tooltip));
+
+        let image_data = render_tray_image(Some(&format_timer_text(paused.elapsed_seconds)), true);
+        if let Ok(icon) = Image::from_bytes(&image_data) {
+            let _ = tray.set_icon(Some(icon));
+        }
+    }
+}
+
 // Update tray icon with live timer display (rendered as combined image)
 #[tauri::command]
 async fn update_tray_timer(elapsed_seconds: i32) -> Result<(), String> {
-    let hours = elapsed_seconds / 3600;
-    let minutes = (elapsed_seconds % 3600) / 60;
-    let seconds = elapsed_seconds % 60;
+    // The pomodoro countdown replaces the elapsed time while a cycle runs
+    if pomodoro::is_active() {
+        return Ok(());
+    }
 
     // Format timer text (no leading space needed since we control positioning)
-    let time_str = if hours > 0 {
-        format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
-    } else {
-        format!("{:02}:{:02}", minutes, seconds)
-    };
+    let time_str = format_timer_text(elapsed_seconds as i64);
 
     // Check if the text changed - skip update if same (prevents flickering)
     {
@@ -479,7 +520,7 @@ async fn update_tray_timer(elapsed_seconds: i32) -> Result<(), String> {
 
     if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
         // Render combined icon + text image
-        let image_data = render_tray_image(Some(&time_str));
+        let image_data = render_tray_image(Some(&time_str), false);
 
         // Set the rendered image as the tray icon (no template mode = no flash)
         if let Ok(icon) = Image::from_bytes(&image_data) {
@@ -492,527 +533,645 @@ async fn update_tray_timer(elapsed_seconds: i32) -> Result<(), String> {
 // Clear timer from tray (show only icon)
 #[tauri::command]
 async fn clear_tray_timer() -> Result<(), String> {
+    // Keep showing the countdown during a break
+    if pomodoro::is_active() {
+        return Ok(());
+    }
+
     // Clear the cache
     *LAST_TIMER_TEXT.lock().unwrap() = None;
 
     if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
         // Render just the icon without text
-        let image_data = render_tray_image(None);
+        let image_data = render_tray_image(None, false);
 
         // No template mode = no flash
         if let Ok(icon) = Image::from_bytes(&image_data) {
             let _ = tray.set_icon(Some(icon));
         }
     }
+
+    show_paused_in_tray();
     Ok(())
 }
 
 // Sync timer state from frontend to backend (for shortcut toggle)
 #[tauri::command]
-async fn sync_timer_state(timer: Option<TimeEntry>, first_project_id: Option<i32>) -> Result<(), String> {
+async fn sync_timer_state(app: tauri::AppHandle, timer: Option<TimeEntry>, first_project_id: Option<i32>) -> Result<(), String> {
     *ACTIVE_TIMER_STATE.lock().unwrap() = timer;
     if let Some(pid) = first_project_id {
         *FIRST_PROJECT_ID.lock().unwrap() = Some(pid);
     }
+    rebuild_tray_menu(&app);
     Ok(())
 }
 
-// Show macOS notification
+// Show macOS notification, optionally with action buttons handled in the backend
 #[tauri::command]
-async fn show_notification(app: tauri::AppHandle, title: String, body: String) -> Result<(), String> {
-    app.notification()
-        .builder()
-        .title(&title)
-        .body(&body)
-        .show()
-        .map_err(|e| e.to_string())?;
+async fn show_notification(
+    app: tauri::AppHandle,
+    title: String,
+    body: String,
+    category: Option<NotificationCategory>,
+    actions: Option<Vec<NotificationAction>>,
+) -> Result<(), String> {
+    notifications::notify(
+        &app,
+        category.unwrap_or(NotificationCategory::General),
+        &title,
+        &body,
+        &actions.unwrap_or_default(),
+    );
     Ok(())
 }
 
-// Toggle timer via global shortcut
-async fn toggle_timer_via_shortcut(app: &tauri::AppHandle) {
-    // Get current timer state
-    let timer_state = ACTIVE_TIMER_STATE.lock().unwrap().clone();
-
-    if let Some(timer) = timer_state {
-        // Timer is running - stop it
-        match stop_timer_internal(timer.id).await {
-            Ok(stopped) => {
-                // Update state
-                *ACTIVE_TIMER_STATE.lock().unwrap() = None;
-
-                // Calculate duration for notification
-                let duration_str = match stopped.duration_minutes {
-                    Some(mins) => {
-                        let total_minutes = mins as i32;
-                        let hours = total_minutes / 60;
-                        let minutes = total_minutes % 60;
-                        if hours > 0 {
-                            format!("{}h {}m", hours, minutes)
-                        } else {
-                            format!("{}m", minutes)
-                        }
-                    }
-                    None => "".to_string()
-                };
-
-                // Show notification
-                let project_name = stopped.project.as_ref()
-                    .map(|p| p.name.clone())
-                    .unwrap_or_else(|| "Timer".to_string());
-
-                let _ = app.notification()
-                    .builder()
-                    .title("Timer Stopped")
-                    .body(&format!("{} - {}", project_name, duration_str))
-                    .show();
-
-                // Update tray tooltip and icon (rendered black, no template)
-                if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
-                    let _ = tray.set_tooltip(Some("FreshTracks - No active timer"));
-                    let icon_data = render_tray_image(None);
-                    if let Ok(icon) = Image::from_bytes(&icon_data) {
-                        let _ = tray.set_icon(Some(icon));
-                    }
-                }
-
-                // Emit event to frontend to sync UI
-                let _ = app.emit("timer-toggled", ());
-            }
-            Err(e) => {
-                eprintln!("Failed to stop timer via shortcut: {}", e);
-            }
-        }
+// Format a duration in minutes as "1h 5m" / "5m"
+fn format_duration_minutes(mins: f64) -> String {
+    let total_minutes = mins as i32;
+    let hours = total_minutes / 60;
+    let minutes = total_minutes % 60;
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
     } else {
-        // No timer running - start one with first project
-        let first_project = FIRST_PROJECT_ID.lock().unwrap().clone();
-
-        if let Some(project_id) = first_project {
-            match start_timer_internal(project_id, None).await {
-                Ok(started) => {
-                    // Update state
-                    *ACTIVE_TIMER_STATE.lock().unwrap() = Some(started.clone());
-
-                    // Show notification
-                    let project_name = started.project.as_ref()
-                        .map(|p| {
-                            if let Some(client) = &p.client {
-                                format!("{} - {}", client.name, p.name)
-                            } else {
-                                p.name.clone()
-                            }
-                        })
-                        .unwrap_or_else(|| "Project".to_string());
-
-                    let _ = app.notification()
-                        .builder()
-                        .title("Timer Started")
-                        .body(&project_name)
-                        .show();
-
-                    // Update tray tooltip (icon will be handled by frontend calling update_tray_timer)
-                    if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
-                        let _ = tray.set_tooltip(Some(&format!("FreshTracks - {} (Running)", project_name)));
-                    }
-
-                    // Emit event to frontend to sync UI
-                    let _ = app.emit("timer-toggled", ());
-                }
-                Err(e) => {
-                    eprintln!("Failed to start timer via shortcut: {}", e);
-                }
-            }
-        } else {
-            // No project available - show window instead
-            if let Some(window) = app.get_webview_window("main") {
-                let _ = window.show();
-                let _ = window.set_focus();
-            }
-        }
+        format!("{}m", minutes)
     }
 }
 
-// Internal helper for starting timer (without tauri::command wrapper)
-async fn start_timer_internal(project_id: i32, description: Option<String>) -> Result<TimeEntry, String> {
-    let client = get_http_client();
+// Total tracked minutes for entries started on the local calendar day of `now`
+fn today_total_minutes(entries: &[TimeEntry], now: DateTime<Utc>) -> f64 {
+    let today = now.with_timezone(&Local).date_naive();
+    entries
+        .iter()
+        .filter(|e| {
+            parse_timestamp(&e.started_at)
+                .map(|dt| dt.with_timezone(&Local).date_naive() == today)
+                .unwrap_or(false)
+        })
+        .map(|e| entry_elapsed_seconds(e, now) as f64 / 60.0)
+        .sum()
+}
 
-    #[derive(Serialize)]
-    struct StartTimerRequest {
-        project_id: i32,
-        description: Option<String>,
+// Show the main window (used by shortcuts, tray menu, and fallbacks)
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
     }
+}
 
-    let body = StartTimerRequest {
-        project_id,
-        description,
-    };
+// Stop a timer and update notification, tray, state, and frontend
+async fn stop_timer_with_feedback(app: &AppHandle, timer_id: i32) -> Result<TimeEntry, String> {
+    let stopped = stop_timer_internal(timer_id).await?;
+    // The stopped entry still carries the start (and resume) times a revert needs
+    let undo_id = undo::record(app, undo::UndoAction::Stopped { entry: stopped.clone() });
+    pomodoro::on_timer_stopped(app);
 
-    let response = client
-        .post(&format!("{}/time-entries", API_BASE))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API error {}: {}", status, body));
-    }
+    // Update state
+    *ACTIVE_TIMER_STATE.lock().unwrap() = None;
+    *PAUSED_TIMER.lock().unwrap() = None;
+    *LAST_TIMER_TEXT.lock().unwrap() = None;
 
-    let timer = response
-        .json::<TimeEntry>()
-        .await
-        .map_err(|e| e.to_string())?;
+    // Calculate duration for notification
+    let duration_str = stopped
+        .duration_minutes
+        .map(format_duration_minutes)
+        .unwrap_or_default();
 
-    Ok(timer)
-}
+    // Show notification
+    let project_name = stopped.project.as_ref()
+        .map(|p| p.name.clone())
+        .unwrap_or_else(|| "Timer".to_string());
+
+    notifications::notify(
+        app,
+        NotificationCategory::Timer,
+        "Timer Stopped",
+        &format!("{} - {}", project_name, duration_str),
+        &[
+            NotificationAction::RestartEntry(stopped.id),
+            NotificationAction::Undo(undo_id),
+            NotificationAction::Open,
+        ],
+    );
 
-// Internal helper for stopping timer
-async fn stop_timer_internal(timer_id: i32) -> Result<TimeEntry, String> {
-    let client = get_http_client();
-
-    let response = client
-        .post(&format!("{}/time-entries/{}/stop", API_BASE, timer_id))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API error {}: {}", status, body));
+    // Update tray tooltip and icon (rendered black, no template)
+    if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
+        let _ = tray.set_tooltip(Some("FreshTracks - No active timer"));
+        let icon_data = render_tray_image(None, false);
+        if let Ok(icon) = Image::from_bytes(&icon_data) {
+            let _ = tray.set_icon(Some(icon));
+        }
     }
 
-    let timer = response
-        .json::<TimeEntry>()
-        .await
-        .map_err(|e| e.to_string())?;
+    // Emit event to frontend to sync UI
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(app).await;
 
-    Ok(timer)
+    Ok(stopped)
 }
 
-// API Commands
-#[tauri::command]
-async fn get_active_timer() -> Result<Option<TimeEntry>, String> {
-    let client = get_http_client();
-    let response = client
-        .get(&format!("{}/active-timer", API_BASE))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if response.status() == 404 || response.status() == 204 {
-        return Ok(None);
-    }
+// Update notification, tray, state, and frontend after a timer has started
+async fn announce_timer_started(app: &AppHandle, started: &TimeEntry, title: &str) {
+    // Update state (starting or resuming anything ends a pause)
+    *ACTIVE_TIMER_STATE.lock().unwrap() = Some(started.clone());
+    *PAUSED_TIMER.lock().unwrap() = None;
+    *LAST_TIMER_TEXT.lock().unwrap() = None;
+    pomodoro::on_timer_started(app, started);
 
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
-    }
+    // Show notification
+    let project_name = project_label(started.project.as_ref());
 
-    if !response.status().is_success() {
-        return Err(format!("API error: {}", response.status()));
-    }
+    notifications::notify(
+        app,
+        NotificationCategory::Timer,
+        title,
+        &project_name,
+        &[NotificationAction::StopTimer, NotificationAction::Open],
+    );
 
-    // Laravel returns null as JSON when no active timer
-    let text = response.text().await.map_err(|e| e.to_string())?;
-    if text == "null" || text.is_empty() {
-        return Ok(None);
+    // Update tray tooltip (icon will be handled by frontend calling update_tray_timer)
+    if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
+        let _ = tray.set_tooltip(Some(&format!("FreshTracks - {} (Running)", project_name)));
     }
 
-    let timer: TimeEntry = serde_json::from_str(&text).map_err(|e| e.to_string())?;
-    Ok(Some(timer))
+    // Emit event to frontend to sync UI
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(app).await;
 }
 
-#[tauri::command]
-async fn start_timer(project_id: i32, description: Option<String>) -> Result<TimeEntry, String> {
-    let client = get_http_client();
-
-    #[derive(Serialize)]
-    struct StartTimerRequest {
-        project_id: i32,
-        description: Option<String>,
-    }
-
-    let body = StartTimerRequest {
-        project_id,
-        description,
-    };
-
-    let response = client
-        .post(&format!("{}/time-entries", API_BASE))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .json(&body)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+// Start a new timer on a project with notification and tray feedback
+async fn start_timer_with_feedback(app: &AppHandle, project_id: i32, description: Option<String>) -> Result<TimeEntry, String> {
+    let started = start_timer_internal(project_id, description).await?;
+    announce_timer_started(app, &started, "Timer Started").await;
+    Ok(started)
+}
 
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
-    }
+// Restart an existing entry with notification and tray feedback
+async fn restart_timer_with_feedback(app: &AppHandle, entry_id: i32) -> Result<TimeEntry, String> {
+    let started = restart_timer(entry_id).await?;
+    announce_timer_started(app, &started, "Timer Started").await;
+    Ok(started)
+}
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API error {}: {}", status, body));
-    }
+// Pause the running timer: stop it on the server and keep its elapsed time frozen locally
+async fn pause_active_timer(app: &AppHandle) -> Result<TimeEntry, String> {
+    let timer = ACTIVE_TIMER_STATE
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "No timer is running".to_string())?;
+
+    let now = Utc::now();
+    let elapsed_before = entry_elapsed_seconds(&timer, now);
+    let stopped = stop_timer_internal(timer.id).await?;
+    pomodoro::on_timer_stopped(app);
+
+    // The server may round duration_minutes, so keep whichever total is larger
+    let elapsed_seconds = elapsed_before.max(entry_elapsed_seconds(&stopped, now));
+
+    *ACTIVE_TIMER_STATE.lock().unwrap() = None;
+    *PAUSED_TIMER.lock().unwrap() = Some(PausedTimer {
+        entry: stopped.clone(),
+        elapsed_seconds,
+        paused_at: now.to_rfc3339(),
+    });
+    show_paused_in_tray();
+
+    notifications::notify(
+        app,
+        NotificationCategory::Timer,
+        "Timer Paused",
+        &format!("{} - {}", project_label(stopped.project.as_ref()), format_timer_text(elapsed_seconds)),
+        &[NotificationAction::RestartEntry(stopped.id), NotificationAction::Open],
+    );
 
-    let timer = response
-        .json::<TimeEntry>()
-        .await
-        .map_err(|e| e.to_string())?;
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(app).await;
 
-    Ok(timer)
+    Ok(stopped)
 }
 
-#[tauri::command]
-async fn stop_timer(timer_id: i32) -> Result<TimeEntry, String> {
-    let client = get_http_client();
-
-    let response = client
-        .post(&format!("{}/time-entries/{}/stop", API_BASE, timer_id))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
-    }
+// Resume the paused timer by restarting the same entry
+async fn resume_paused_timer(app: &AppHandle) -> Result<TimeEntry, String> {
+    let paused = PAUSED_TIMER
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "No timer is paused".to_string())?;
+
+    let resumed = restart_timer(paused.entry.id).await?;
+    announce_timer_started(app, &resumed, "Timer Resumed").await;
+    Ok(resumed)
+}
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API error {}: {}", status, body));
+// Pause if running, resume if paused
+async fn toggle_pause(app: &AppHandle) -> Result<TimeEntry, String> {
+    if ACTIVE_TIMER_STATE.lock().unwrap().is_some() {
+        pause_active_timer(app).await
+    } else {
+        resume_paused_timer(app).await
     }
-
-    let timer = response
-        .json::<TimeEntry>()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    Ok(timer)
 }
 
-#[tauri::command]
-async fn get_time_entries() -> Result<Vec<TimeEntry>, String> {
-    let client = get_http_client();
-
-    let response = client
-        .get(&format!("{}/time-entries", API_BASE))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+// Drop the paused state without resuming (entry is already stopped on the server)
+fn finish_paused_timer(app: &AppHandle) {
+    *PAUSED_TIMER.lock().unwrap() = None;
+    *LAST_TIMER_TEXT.lock().unwrap() = None;
 
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
+    if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
+        let _ = tray.set_tooltip(Some("FreshTracks - No active timer"));
+        let icon_data = render_tray_image(None, false);
+        if let Ok(icon) = Image::from_bytes(&icon_data) {
+            let _ = tray.set_icon(Some(icon));
+        }
     }
 
-    if !response.status().is_success() {
-        return Err(format!("API error: {}", response.status()));
-    }
+    let _ = app.emit("timer-toggled", ());
+    rebuild_tray_menu(app);
+}
 
-    let entries: Vec<TimeEntry> = response
-        .json()
-        .await
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+async fn pause_timer(app: tauri::AppHandle) -> Result<TimeEntry, String> {
+    pause_active_timer(&app).await
+}
 
-    Ok(entries)
+#[tauri::command]
+async fn resume_timer(app: tauri::AppHandle) -> Result<TimeEntry, String> {
+    resume_paused_timer(&app).await
 }
 
 #[tauri::command]
-async fn restart_timer(timer_id: i32) -> Result<TimeEntry, String> {
-    let client = get_http_client();
+async fn get_paused_timer() -> Result<Option<PausedTimer>, String> {
+    Ok(PAUSED_TIMER.lock().unwrap().clone())
+}
 
-    let response = client
-        .post(&format!("{}/time-entries/{}/restart", API_BASE, timer_id))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+// Toggle timer via global shortcut
+async fn toggle_timer_via_shortcut(app: &tauri::AppHandle) {
+    // Get current timer state
+    let timer_state = ACTIVE_TIMER_STATE.lock().unwrap().clone();
 
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
+    if let Some(timer) = timer_state {
+        // Timer is running - stop it
+        if let Err(e) = stop_timer_with_feedback(app, timer.id).await {
+            eprintln!("Failed to stop timer via shortcut: {}", e);
+        }
+    } else {
+        // No timer running - start one using the configured quick-start policy
+        quick_start_timer(app).await;
     }
+}
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API error {}: {}", status, body));
-    }
+// Start a timer on the target chosen by the quick-start policy
+async fn quick_start_timer(app: &AppHandle) {
+    let entries = match get_time_entries().await {
+        Ok(entries) => {
+            *RECENT_ENTRIES.lock().unwrap() = entries.clone();
+            entries
+        }
+        Err(e) => {
+            eprintln!("Failed to fetch entries for quick-start, using cache: {}", e);
+            RECENT_ENTRIES.lock().unwrap().clone()
+        }
+    };
+    let policy = preferences::get().quick_start;
+    let fallback_project = *FIRST_PROJECT_ID.lock().unwrap();
 
-    let timer = response
-        .json::<TimeEntry>()
-        .await
-        .map_err(|e| e.to_string())?;
+    let result = match quick_start::choose_target(&policy, &entries, Utc::now(), fallback_project) {
+        Some(quick_start::QuickStartTarget::RestartEntry(entry_id)) => {
+            restart_timer_with_feedback(app, entry_id).await
+        }
+        Some(quick_start::QuickStartTarget::StartProject(project_id)) => {
+            start_timer_with_feedback(app, project_id, None).await
+        }
+        None => {
+            // No project available - show window instead
+            show_main_window(app);
+            return;
+        }
+    };
 
-    Ok(timer)
+    if let Err(e) = result {
+        eprintln!("Failed to quick-start timer: {}", e);
+    }
 }
 
-#[tauri::command]
-async fn delete_entry(entry_id: i32) -> Result<(), String> {
-    let client = get_http_client();
-
-    let response = client
-        .delete(&format!("{}/time-entries/{}", API_BASE, entry_id))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
+// Build the tray menu from cached state (active timer, recent projects, today's entries, sync)
+fn build_tray_menu(app: &AppHandle) -> tauri::Result<Menu<Wry>> {
+    let active_timer = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+    let paused_timer = PAUSED_TIMER.lock().unwrap().clone();
+    let projects = RECENT_PROJECTS.lock().unwrap().clone();
+    let entries = RECENT_ENTRIES.lock().unwrap().clone();
+    let sync_connected = *SYNC_CONNECTED.lock().unwrap();
+    let pomodoro_state = pomodoro::current_state();
+    let now = Utc::now();
+
+    let menu = Menu::new(app)?;
+
+    // Current timer with Stop, or the last entry with Restart
+    if let Some(timer) = &active_timer {
+        let since = parse_timestamp(timer.resumed_at.as_deref().unwrap_or(&timer.started_at))
+            .map(|dt| format!(" (since {})", dt.with_timezone(&Local).format("%H:%M")))
+            .unwrap_or_default();
+        let label = format!("● {}{}", project_label(timer.project.as_ref()), since);
+        menu.append(&MenuItem::with_id(app, "current_timer", label, false, None::<&str>)?)?;
+        menu.append(&MenuItem::with_id(app, "pause_timer", "Pause Timer", true, None::<&str>)?)?;
+        menu.append(&MenuItem::with_id(app, "stop_timer", "Stop Timer", true, None::<&str>)?)?;
+        if pomodoro_state.is_none() {
+            menu.append(&MenuItem::with_id(app, "start_pomodoro", "Start Pomodoro", true, None::<&str>)?)?;
+        }
+    } else if let Some(paused) = &paused_timer {
+        let label = format!(
+            "❚❚ {} ({})",
+            project_label(paused.entry.project.as_ref()),
+            format_timer_text(paused.elapsed_seconds)
+        );
+        menu.append(&MenuItem::with_id(app, "current_timer", label, false, None::<&str>)?)?;
+        menu.append(&MenuItem::with_id(app, "resume_timer", "Resume Timer", true, None::<&str>)?)?;
+        menu.append(&MenuItem::with_id(app, "finish_paused", "Stop Timer", true, None::<&str>)?)?;
+    } else {
+        menu.append(&MenuItem::with_id(app, "current_timer", "No timer running", false, None::<&str>)?)?;
+        if let Some(last) = last_stopped_entry(&entries) {
+            let label = format!("Restart {}", project_label(last.project.as_ref()));
+            menu.append(&MenuItem::with_id(app, format!("restart_entry:{}", last.id), label, true, None::<&str>)?)?;
+        }
     }
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API error {}: {}", status, body));
+    // Pomodoro cycle (also shown during a break that stopped the timer)
+    if let Some(state) = &pomodoro_state {
+        let label = format!("🍅 {} · {} done", state.phase.label(), state.completed_work_sessions);
+        menu.append(&MenuItem::with_id(app, "pomodoro_status", label, false, None::<&str>)?)?;
+        menu.append(&MenuItem::with_id(app, "stop_pomodoro", "Stop Pomodoro", true, None::<&str>)?)?;
     }
 
-    Ok(())
-}
-
-#[tauri::command]
-async fn update_entry(entry_id: i32, data: UpdateEntryData) -> Result<TimeEntry, String> {
-    let client = get_http_client();
-
-    let response = client
-        .put(&format!("{}/time-entries/{}", API_BASE, entry_id))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .json(&data)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
-
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
+    // Saved templates
+    let templates = preferences::get().templates;
+    if !templates.is_empty() {
+        let templates_menu = Submenu::with_id(app, "templates", "Start Template", true)?;
+        for template in &templates {
+            let id = format!("template:{}", template.id);
+            templates_menu.append(&MenuItem::with_id(app, id, &template.name, true, None::<&str>)?)?;
+        }
+        menu.append(&templates_menu)?;
     }
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        return Err(format!("API error {}: {}", status, body));
+    // "Start on…" submenu with recent projects grouped by client
+    let start_menu = Submenu::with_id(app, "start_on", "Start on…", !projects.is_empty())?;
+    let mut clients: Vec<(String, Vec<&Project>)> = Vec::new();
+    for project in &projects {
+        match &project.client {
+            Some(client) => match clients.iter_mut().find(|(name, _)| *name == client.name) {
+                Some((_, group)) => group.push(project),
+                None => clients.push((client.name.clone(), vec![project])),
+            },
+            None => {
+                let id = format!("start_project:{}", project.id);
+                start_menu.append(&MenuItem::with_id(app, id, &project.name, true, None::<&str>)?)?;
+            }
+        }
     }
+    clients.sort_by_key(|(name, _)| name.to_lowercase());
+    for (client_name, group) in clients {
+        let client_menu = Submenu::new(app, &client_name, true)?;
+        for project in group {
+            let id = format!("start_project:{}", project.id);
+            client_menu.append(&MenuItem::with_id(app, id, &project.name, true, None::<&str>)?)?;
+        }
+        start_menu.append(&client_menu)?;
+    }
+    menu.append(&start_menu)?;
 
-    let entry = response
-        .json::<TimeEntry>()
-        .await
-        .map_err(|e| e.to_string())?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
 
-    Ok(entry)
-}
+    // Today's total
+    let today_label = format!("Today: {}", format_duration_minutes(today_total_minutes(&entries, now)));
+    menu.append(&MenuItem::with_id(app, "today_total", today_label, false, None::<&str>)?)?;
 
-#[tauri::command]
-async fn get_recent_projects() -> Result<Vec<Project>, String> {
-    let client = get_http_client();
+    // Sync status
+    let sync_label = if sync_connected { "Sync: Connected" } else { "Sync: Offline" };
+    menu.append(&MenuItem::with_id(app, "sync_status", sync_label, false, None::<&str>)?)?;
 
-    let response = client
-        .get(&format!("{}/projects", API_BASE))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
 
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
-    }
+    menu.append(&MenuItem::with_id(app, "show", "Show Timer", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "open_web_app", "Open Web App", true, None::<&str>)?)?;
+    menu.append(&MenuItem::with_id(app, "preferences", "Preferences…", true, None::<&str>)?)?;
 
-    if !response.status().is_success() {
-        return Err(format!("API error: {}", response.status()));
-    }
+    menu.append(&PredefinedMenuItem::separator(app)?)?;
 
-    let projects: Vec<Project> = response
-        .json()
-        .await
-        .map_err(|e| e.to_string())?;
+    menu.append(&MenuItem::with_id(app, "quit", "Quit FreshTracks", true, None::<&str>)?)?;
 
-    Ok(projects)
+    Ok(menu)
 }
 
-#[tauri::command]
-async fn get_clients() -> Result<Vec<Client>, String> {
-    let client = get_http_client();
+// Rebuild the tray menu from cached state
+fn rebuild_tray_menu(app: &AppHandle) {
+    match build_tray_menu(app) {
+        Ok(menu) => {
+            if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
+                let _ = tray.set_menu(Some(menu));
+            }
+        }
+        Err(e) => eprintln!("Failed to build tray menu: {}", e),
+    }
+}
 
-    let response = client
-        .get(&format!("{}/clients", API_BASE))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+// Fetch fresh data for the tray menu, then rebuild it
+async fn refresh_tray_menu_data(app: &AppHandle) {
+    match get_recent_projects().await {
+        Ok(projects) => *RECENT_PROJECTS.lock().unwrap() = projects,
+        Err(e) => eprintln!("Failed to refresh recent projects for tray menu: {}", e),
+    }
 
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
+    match get_time_entries().await {
+        Ok(entries) => {
+            // A paused entry deleted elsewhere can no longer be resumed
+            let mut paused = PAUSED_TIMER.lock().unwrap();
+            if paused.as_ref().is_some_and(|p| !entries.iter().any(|e| e.id == p.entry.id)) {
+                *paused = None;
+            }
+            drop(paused);
+            *RECENT_ENTRIES.lock().unwrap() = entries;
+        }
+        Err(e) => eprintln!("Failed to refresh time entries for tray menu: {}", e),
     }
 
-    if !response.status().is_success() {
-        return Err(format!("API error: {}", response.status()));
+    match get_active_timer().await {
+        Ok(timer) => {
+            // Anything running (possibly started from the web app) ends a pause
+            if timer.is_some() {
+                *PAUSED_TIMER.lock().unwrap() = None;
+            }
+            *ACTIVE_TIMER_STATE.lock().unwrap() = timer;
+        }
+        Err(e) => eprintln!("Failed to refresh active timer for tray menu: {}", e),
     }
 
-    let clients: Vec<Client> = response
-        .json()
-        .await
-        .map_err(|e| e.to_string())?;
+    rebuild_tray_menu(app);
+}
 
-    Ok(clients)
+// Handle a click on a tray menu item
+fn handle_tray_menu_event(app: &AppHandle, id: &str) {
+    match id {
+        "quit" => {
+            app.exit(0);
+        }
+        "show" => {
+            show_main_window(app);
+        }
+        "preferences" => {
+            show_main_window(app);
+            let _ = app.emit("open-preferences", ());
+        }
+        "open_web_app" => {
+            #[allow(deprecated)]
+            if let Err(e) = app.shell().open(WEB_APP_URL, None) {
+                eprintln!("Failed to open web app: {}", e);
+            }
+        }
+        "pause_timer" | "resume_timer" => {
+            let app_clone = app.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = toggle_pause(&app_clone).await {
+                    eprintln!("Failed to pause/resume timer from tray menu: {}", e);
+                }
+            });
+        }
+        "finish_paused" => {
+            finish_paused_timer(app);
+        }
+        "start_pomodoro" => {
+            if let Err(e) = pomodoro::start(app) {
+                eprintln!("Failed to start pomodoro from tray menu: {}", e);
+            }
+        }
+        "stop_pomodoro" => {
+            let app_clone = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = pomodoro::stop_pomodoro(app_clone).await;
+            });
+        }
+        "stop_timer" => {
+            let timer = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+            if let Some(timer) = timer {
+                let app_clone = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = stop_timer_with_feedback(&app_clone, timer.id).await {
+                        eprintln!("Failed to stop timer from tray menu: {}", e);
+                    }
+                });
+            }
+        }
+        _ => {
+            if let Some(project_id) = id.strip_prefix("start_project:").and_then(|v| v.parse::<i32>().ok()) {
+                let app_clone = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = start_timer_with_feedback(&app_clone, project_id, None).await {
+                        eprintln!("Failed to start timer from tray menu: {}", e);
+                    }
+                });
+            } else if let Some(template_id) = id.strip_prefix("template:").and_then(|v| v.parse::<u32>().ok()) {
+                let app_clone = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = templates::start(&app_clone, template_id).await {
+                        eprintln!("Failed to start template from tray menu: {}", e);
+                    }
+                });
+            } else if let Some(entry_id) = id.strip_prefix("restart_entry:").and_then(|v| v.parse::<i32>().ok()) {
+                let app_clone = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = restart_timer_with_feedback(&app_clone, entry_id).await {
+                        eprintln!("Failed to restart timer from tray menu: {}", e);
+                    }
+                });
+            }
+        }
+    }
 }
 
+// Refresh the tray menu (called by the frontend after it changes entries)
 #[tauri::command]
-async fn get_client_projects(client_id: i32) -> Result<Vec<Project>, String> {
-    let client = get_http_client();
-
-    let response = client
-        .get(&format!("{}/clients/{}/projects", API_BASE, client_id))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+async fn refresh_tray_menu(app: tauri::AppHandle) -> Result<(), String> {
+    refresh_tray_menu_data(&app).await;
+    Ok(())
+}
 
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
-    }
+// API commands (the requests themselves live in the freshtracks crate)
+#[tauri::command]
+async fn get_active_timer() -> Result<Option<TimeEntry>, String> {
+    freshtracks::get_active_timer().await
+}
 
-    if !response.status().is_success() {
-        return Err(format!("API error: {}", response.status()));
-    }
+#[tauri::command]
+async fn start_timer(project_id: i32, description: Option<String>) -> Result<TimeEntry, String> {
+    freshtracks::start_timer(project_id, description).await
+}
 
-    let projects: Vec<Project> = response
-        .json()
-        .await
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+async fn stop_timer(app: tauri::AppHandle, timer_id: i32) -> Result<TimeEntry, String> {
+    let timer = stop_timer_internal(timer_id).await?;
+    undo::record(&app, undo::UndoAction::Stopped { entry: timer.clone() });
+    Ok(timer)
+}
 
-    Ok(projects)
+#[tauri::command]
+async fn get_time_entries() -> Result<Vec<TimeEntry>, String> {
+    freshtracks::get_time_entries().await
 }
 
+// Get time entries for a date range, optionally filtered by client, project and billable flag
 #[tauri::command]
-async fn get_settings() -> Result<UserSettings, String> {
-    let client = get_http_client();
+async fn get_time_entries_range(filter: TimeEntryFilter) -> Result<Vec<TimeEntry>, String> {
+    fetch_time_entries_range(&filter).await
+}
 
-    let response = client
-        .get(&format!("{}/settings", API_BASE))
-        .header("X-Widget-Secret", WIDGET_SECRET)
-        .send()
-        .await
-        .map_err(|e| e.to_string())?;
+#[tauri::command]
+async fn restart_timer(timer_id: i32) -> Result<TimeEntry, String> {
+    freshtracks::restart_timer(timer_id).await
+}
 
-    if response.status() == 401 {
-        return Err("Widget authentication failed. Check your secret.".to_string());
+// Delete an entry, keeping a snapshot so it can be undone
+#[tauri::command]
+async fn delete_entry(app: tauri::AppHandle, entry_id: i32) -> Result<(), String> {
+    let before = undo::snapshot(entry_id).await;
+    delete_entry_internal(entry_id).await?;
+    if let Some(entry) = before {
+        undo::record(&app, undo::UndoAction::Deleted { entry });
     }
+    Ok(())
+}
 
-    if !response.status().is_success() {
-        return Ok(UserSettings::default());
+// Update an entry, keeping a snapshot of the previous values so it can be undone
+#[tauri::command]
+async fn update_entry(app: tauri::AppHandle, entry_id: i32, data: UpdateEntryData) -> Result<TimeEntry, String> {
+    let warnings = validation::check_update(entry_id, &data).await?;
+    validation::reject_invalid(&warnings)?;
+
+    let before = undo::snapshot(entry_id).await;
+    let updated = update_entry_internal(entry_id, data).await?;
+    if let Some(entry) = before {
+        undo::record(&app, undo::UndoAction::Updated { entries: vec![entry] });
     }
+    Ok(updated)
+}
 
-    let settings: UserSettings = response
-        .json()
-        .await
-        .unwrap_or_default();
+#[tauri::command]
+async fn get_recent_projects() -> Result<Vec<Project>, String> {
+    freshtracks::get_recent_projects().await
+}
 
-    Ok(settings)
+#[tauri::command]
+async fn get_clients() -> Result<Vec<Client>, String> {
+    freshtracks::get_clients().await
+}
+
+#[tauri::command]
+async fn get_client_projects(client_id: i32) -> Result<Vec<Project>, String> {
+    freshtracks::get_client_projects(client_id).await
+}
+
+#[tauri::command]
+async fn get_settings() -> Result<UserSettings, String> {
+    freshtracks::get_settings().await
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -1020,6 +1179,7 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec!["--hidden"]),
@@ -1027,12 +1187,9 @@ pub fn run() {
         .plugin({
             // Global shortcut plugin with handler
             tauri_plugin_global_shortcut::Builder::new()
-                .with_handler(|app, _shortcut, event| {
+                .with_handler(|app, shortcut, event| {
                     if event.state() == tauri_plugin_global_shortcut::ShortcutState::Pressed {
-                        let app_clone = app.clone();
-                        tauri::async_runtime::spawn(async move {
-                            toggle_timer_via_shortcut(&app_clone).await;
-                        });
+                        shortcuts::handle_shortcut(app, shortcut);
                     }
                 })
                 .build()
@@ -1047,13 +1204,13 @@ pub fn run() {
                 )?;
             }
 
-            // Register global shortcut (Cmd+Shift+T)
-            let shortcut = Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyT);
-            if let Err(e) = app.global_shortcut().register(shortcut) {
-                eprintln!("Failed to register global shortcut: {}", e);
-            } else {
-                println!("Global shortcut Cmd+Shift+T registered successfully");
-            }
+            // Load local preferences and register the configured global shortcuts
+            preferences::load(app.handle());
+            shortcuts::register_all(app.handle());
+            pomodoro::load(app.handle());
+            http_api::restart_server(app.handle());
+            #[cfg(unix)]
+            rpc::start_server(app.handle());
 
             // Start WebSocket connection for real-time sync
             let app_handle = app.handle().clone();
@@ -1061,13 +1218,35 @@ pub fn run() {
                 connect_websocket(app_handle).await;
             });
 
-            // Create tray menu
-            let quit_item = MenuItem::with_id(app, "quit", "Quit FreshTracks", true, None::<&str>)?;
-            let show_item = MenuItem::with_id(app, "show", "Show Timer", true, None::<&str>)?;
-            let menu = Menu::with_items(app, &[&show_item, &quit_item])?;
+            // Watch for user inactivity while a timer is running
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                idle::run_idle_monitor(app_handle).await;
+            });
+
+            // Watch for sleep/wake and screen lock to resync after time away
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                power::run_power_monitor(app_handle).await;
+            });
+
+            // Evaluate forgotten/long-running timer reminders
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                reminders::run_reminder_loop(app_handle).await;
+            });
+
+            // Drive pomodoro phases and the tray countdown
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                pomodoro::run_pomodoro_loop(app_handle).await;
+            });
+
+            // Create tray menu (rebuilt from cached state whenever the timer changes)
+            let menu = build_tray_menu(app.handle())?;
 
             // Load tray icon using our render function (black, non-template)
-            let icon_data = render_tray_image(None);
+            let icon_data = render_tray_image(None, false);
             let icon = Image::from_bytes(&icon_data)
                 .expect("Failed to load tray icon");
 
@@ -1079,18 +1258,7 @@ pub fn run() {
                 .show_menu_on_left_click(false)
                 .tooltip("FreshTracks - Time Tracking")
                 .on_menu_event(|app, event| {
-                    match event.id.as_ref() {
-                        "quit" => {
-                            app.exit(0);
-                        }
-                        "show" => {
-                            if let Some(window) = app.get_webview_window("main") {
-                                let _ = window.show();
-                                let _ = window.set_focus();
-                            }
-                        }
-                        _ => {}
-                    }
+                    handle_tray_menu_event(app, event.id.as_ref());
                 })
                 .on_tray_icon_event(|tray, event| {
                     if let TrayIconEvent::Click {
@@ -1122,6 +1290,12 @@ pub fn run() {
             // Store tray reference for later updates
             *TRAY_ICON.lock().unwrap() = Some(tray);
 
+            // Populate the tray menu with recent projects and today's entries
+            let app_handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                refresh_tray_menu_data(&app_handle).await;
+            });
+
             // Hide window when it loses focus
             let main_window = app.get_webview_window("main").unwrap();
             let window_clone = main_window.clone();
@@ -1140,7 +1314,26 @@ pub fn run() {
             restart_timer,
             delete_entry,
             update_entry,
+            entries::create_entry,
+            entries::split_entry,
+            entries::merge_entries,
+            entries::bulk_update_entries,
+            undo::undo_last_action,
+            undo::get_undo_stack,
+            templates::get_templates,
+            templates::save_template,
+            templates::delete_template,
+            templates::start_from_template,
+            suggestions::suggest_descriptions,
+            search::search_projects,
+            palette::palette_search,
+            palette::palette_start,
+            palette::palette_stop,
+            palette::hide_palette,
+            http_api::set_http_api_settings,
+            http_api::regenerate_http_api_token,
             get_time_entries,
+            get_time_entries_range,
             get_recent_projects,
             get_clients,
             get_client_projects,
@@ -1150,6 +1343,41 @@ pub fn run() {
             clear_tray_timer,
             sync_timer_state,
             show_notification,
+            refresh_tray_menu,
+            pause_timer,
+            resume_timer,
+            get_paused_timer,
+            preferences::get_preferences,
+            quick_start::set_quick_start_policy,
+            idle::get_idle_prompt,
+            idle::resolve_idle,
+            idle::set_idle_settings,
+            power::get_away_prompt,
+            power::resolve_away,
+            power::set_power_settings,
+            reminders::set_reminder_settings,
+            notifications::set_notification_settings,
+            pomodoro::start_pomodoro,
+            pomodoro::stop_pomodoro,
+            pomodoro::get_pomodoro_state,
+            pomodoro::get_pomodoro_stats,
+            pomodoro::set_pomodoro_settings,
+            reports::get_report,
+            reports::get_daily_summary,
+            reports::get_weekly_summary,
+            reports::set_report_settings,
+            export::export_entries,
+            export::export_entries_to_file,
+            validation::validate_entries,
+            validation::check_entry_update,
+            validation::set_validation_settings,
+            calendar::export_ics,
+            calendar::import_ics,
+            calendar::confirm_calendar_drafts,
+            shortcuts::get_shortcuts,
+            shortcuts::bind_shortcut,
+            shortcuts::unbind_shortcut,
+            shortcuts::reset_shortcuts,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
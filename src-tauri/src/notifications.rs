@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::preferences;
+use crate::undo;
+use crate::{
+    restart_timer_with_feedback, quick_start_timer, show_main_window, stop_timer_with_feedback,
+    ACTIVE_TIMER_STATE,
+};
+
+// How long "Snooze" postpones a notification
+const SNOOZE_MINUTES: u64 = 15;
+
+// Kinds of notifications, each of which can be muted
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    // Timer started/stopped/paused feedback
+    Timer,
+    Reminder,
+    Idle,
+    Away,
+    // Pomodoro work/break transitions
+    Pomodoro,
+    // Anything sent by the frontend without a category
+    General,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub muted: Vec<NotificationCategory>,
+}
+
+// Buttons that can be attached to a native notification
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationAction {
+    StopTimer,
+    StartTimer,
+    RestartEntry(i32),
+    Snooze,
+    Open,
+    // Revert the action recorded under this undo id (see undo::record)
+    Undo(u64),
+}
+
+impl NotificationAction {
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    fn label(self) -> &'static str {
+        match self {
+            NotificationAction::StopTimer => "Stop",
+            NotificationAction::StartTimer => "Start",
+            NotificationAction::RestartEntry(_) => "Restart",
+            NotificationAction::Snooze => "Snooze 15 min",
+            NotificationAction::Open => "Open",
+            NotificationAction::Undo(_) => "Undo",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Notification {
+    category: NotificationCategory,
+    title: String,
+    body: String,
+    actions: Vec<NotificationAction>,
+}
+
+// Show a notification unless its category is muted. Actions become buttons whose
+// callbacks run in the backend; where buttons aren't supported a plain notification is shown.
+pub fn notify(app: &AppHandle, category: NotificationCategory, title: &str, body: &str, actions: &[NotificationAction]) {
+    if preferences::get().notifications.muted.contains(&category) {
+        return;
+    }
+
+    deliver(app, Notification {
+        category,
+        title: title.to_string(),
+        body: body.to_string(),
+        actions: actions.to_vec(),
+    });
+}
+
+fn deliver(app: &AppHandle, notification: Notification) {
+    if notification.actions.is_empty() {
+        show_plain(app, &notification.title, &notification.body);
+        return;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::sync::mpsc::TrySendError;
+
+        // The response thread takes a notification only while it is idle; when it is still
+        // waiting on an earlier one, this one goes out without buttons rather than queueing
+        let (responder, fresh) = responder(app);
+        let rejected = if fresh {
+            responder.send(notification).err().map(|e| e.0)
+        } else {
+            match responder.try_send(notification) {
+                Ok(()) => None,
+                Err(TrySendError::Full(n) | TrySendError::Disconnected(n)) => Some(n),
+            }
+        };
+        if let Some(notification) = rejected {
+            show_plain(app, &notification.title, &notification.body);
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    show_plain(app, &notification.title, &notification.body);
+}
+
+// Delivering with buttons blocks until the user responds, so a single thread delivers
+// actionable notifications and waits for the responses, instead of one thread per notification
+#[cfg(target_os = "macos")]
+static RESPONDER: once_cell::sync::Lazy<std::sync::Mutex<Option<std::sync::mpsc::SyncSender<Notification>>>> =
+    once_cell::sync::Lazy::new(|| std::sync::Mutex::new(None));
+
+// The response thread's channel, and whether the thread was just started
+#[cfg(target_os = "macos")]
+fn responder(app: &AppHandle) -> (std::sync::mpsc::SyncSender<Notification>, bool) {
+    use tauri::Manager;
+
+    let mut responder = RESPONDER.lock().unwrap();
+    if let Some(sender) = responder.as_ref() {
+        return (sender.clone(), false);
+    }
+
+    // Rendezvous channel: a send only succeeds once the thread is waiting for work
+    let (sender, receiver) = std::sync::mpsc::sync_channel::<Notification>(0);
+    let app = app.clone();
+    let bundle_id = app.config().identifier.clone();
+    std::thread::spawn(move || {
+        for notification in receiver {
+            let chosen = match send_native(&bundle_id, &notification) {
+                Ok(mac_notification_sys::NotificationResponse::ActionButton(label)) => {
+                    notification.actions.iter().copied().find(|a| a.label() == label)
+                }
+                Ok(_) => None,
+                Err(e) => {
+                    eprintln!("Failed to show actionable notification: {}", e);
+                    None
+                }
+            };
+
+            if let Some(action) = chosen {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    run_action(&app, action, &notification).await;
+                });
+            }
+        }
+    });
+
+    *responder = Some(sender.clone());
+    (sender, true)
+}
+
+fn show_plain(app: &AppHandle, title: &str, body: &str) {
+    let _ = app.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show();
+}
+
+#[cfg(target_os = "macos")]
+fn send_native(bundle_id: &str, notification: &Notification) -> Result<mac_notification_sys::NotificationResponse, String> {
+    use mac_notification_sys::MainButton;
+
+    // Attribute the notification to this app (errors if already set, which is fine)
+    let _ = mac_notification_sys::set_application(bundle_id);
+
+    let labels: Vec<&str> = notification.actions.iter().map(|a| a.label()).collect();
+    let main_button = if labels.len() == 1 {
+        MainButton::SingleAction(labels[0])
+    } else {
+        MainButton::DropdownActions("Actions", &labels)
+    };
+
+    mac_notification_sys::Notification::new()
+        .title(&notification.title)
+        .message(&notification.body)
+        .main_button(main_button)
+        .close_button("Dismiss")
+        .send()
+        .map_err(|e| e.to_string())
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+async fn run_action(app: &AppHandle, action: NotificationAction, notification: &Notification) {
+    match action {
+        NotificationAction::StopTimer => {
+            let timer = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+            if let Some(timer) = timer {
+                if let Err(e) = stop_timer_with_feedback(app, timer.id).await {
+                    eprintln!("Failed to stop timer from notification: {}", e);
+                }
+            }
+        }
+        NotificationAction::StartTimer => {
+            if ACTIVE_TIMER_STATE.lock().unwrap().is_none() {
+                quick_start_timer(app).await;
+            }
+        }
+        NotificationAction::RestartEntry(entry_id) => {
+            if let Err(e) = restart_timer_with_feedback(app, entry_id).await {
+                eprintln!("Failed to restart timer from notification: {}", e);
+            }
+        }
+        NotificationAction::Snooze => {
+            let app = app.clone();
+            let notification = notification.clone();
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(tokio::time::Duration::from_secs(SNOOZE_MINUTES * 60)).await;
+                // Re-check mute in case it changed while snoozed
+                if !preferences::get().notifications.muted.contains(&notification.category) {
+                    deliver(&app, notification);
+                }
+            });
+        }
+        NotificationAction::Open => show_main_window(app),
+        NotificationAction::Undo(undo_id) => {
+            if let Err(e) = undo::undo_by_id(app, undo_id).await {
+                eprintln!("Failed to undo from notification: {}", e);
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn set_notification_settings(settings: NotificationSettings) -> Result<NotificationSettings, String> {
+    let prefs = preferences::update(|prefs| prefs.notifications = settings)?;
+    Ok(prefs.notifications)
+}
@@ -0,0 +1,186 @@
+use freshtracks::fuzzy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::preferences;
+use crate::search;
+use crate::templates;
+use crate::{start_timer_with_feedback, stop_timer_with_feedback, TimeEntry, ACTIVE_TIMER_STATE};
+
+const PALETTE_LABEL: &str = "palette";
+const PALETTE_WIDTH: f64 = 560.0;
+const PALETTE_HEIGHT: f64 = 340.0;
+const MAX_PALETTE_ITEMS: usize = 10;
+
+// What selecting a palette item starts
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PaletteTarget {
+    Project { project_id: i32 },
+    Template { template_id: u32 },
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PaletteItem {
+    pub target: PaletteTarget,
+    pub label: String,
+    // Template description, or None for projects
+    pub detail: Option<String>,
+    pub score: f64,
+}
+
+// The palette window, created hidden on first use
+fn palette_window(app: &AppHandle) -> tauri::Result<WebviewWindow> {
+    if let Some(window) = app.get_webview_window(PALETTE_LABEL) {
+        return Ok(window);
+    }
+
+    let window = WebviewWindowBuilder::new(app, PALETTE_LABEL, WebviewUrl::App("index.html#palette".into()))
+        .title("FreshTracks Quick Start")
+        .inner_size(PALETTE_WIDTH, PALETTE_HEIGHT)
+        .decorations(false)
+        .transparent(true)
+        .shadow(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .resizable(false)
+        .visible(false)
+        .build()?;
+
+    // Dismiss like a spotlight panel when focus moves elsewhere
+    let window_clone = window.clone();
+    window.on_window_event(move |event| {
+        if let tauri::WindowEvent::Focused(false) = event {
+            let _ = window_clone.hide();
+        }
+    });
+
+    Ok(window)
+}
+
+// Center horizontally in the upper third of the monitor under the cursor
+fn position_palette(app: &AppHandle, window: &WebviewWindow) {
+    let monitor = app
+        .cursor_position()
+        .ok()
+        .and_then(|cursor| app.monitor_from_point(cursor.x, cursor.y).ok().flatten())
+        .or_else(|| app.primary_monitor().ok().flatten());
+    let Some(monitor) = monitor else {
+        let _ = window.center();
+        return;
+    };
+
+    let area = monitor.size();
+    let origin = monitor.position();
+    let size = window.outer_size().unwrap_or_default();
+    let x = origin.x + (area.width as i32 - size.width as i32) / 2;
+    let y = origin.y + area.height as i32 / 4;
+    let _ = window.set_position(PhysicalPosition::new(x, y));
+}
+
+pub fn show_palette(app: &AppHandle) {
+    match palette_window(app) {
+        Ok(window) => {
+            position_palette(app, &window);
+            let _ = window.show();
+            let _ = window.set_focus();
+            // Let the frontend clear the previous query
+            let _ = app.emit_to(PALETTE_LABEL, "palette-opened", ());
+        }
+        Err(e) => eprintln!("Failed to open command palette: {}", e),
+    }
+}
+
+pub fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(PALETTE_LABEL) {
+        let _ = window.hide();
+    }
+}
+
+pub fn toggle_palette(app: &AppHandle) {
+    let visible = app
+        .get_webview_window(PALETTE_LABEL)
+        .is_some_and(|w| w.is_visible().unwrap_or(false));
+    if visible {
+        hide(app);
+    } else {
+        show_palette(app);
+    }
+}
+
+// Templates and projects matching `query`, best first
+pub async fn palette_items(query: &str) -> Vec<PaletteItem> {
+    let mut items: Vec<PaletteItem> = preferences::get()
+        .templates
+        .into_iter()
+        .filter_map(|template| {
+            let text = format!("{} {}", template.name, template.description.clone().unwrap_or_default());
+            // Templates rank above equally good project matches
+            let score = fuzzy::score(query, &text)? * 2.0 + 1.0;
+            Some(PaletteItem {
+                target: PaletteTarget::Template { template_id: template.id },
+                label: template.name.clone(),
+                detail: template.entry_description(),
+                score,
+            })
+        })
+        .collect();
+
+    items.extend(search::search(query, MAX_PALETTE_ITEMS).await.into_iter().map(|m| PaletteItem {
+        target: PaletteTarget::Project { project_id: m.project.id },
+        label: m.label,
+        detail: None,
+        score: m.score,
+    }));
+
+    items.sort_by(|a, b| b.score.total_cmp(&a.score));
+    items.truncate(MAX_PALETTE_ITEMS);
+    items
+}
+
+// Start a timer on a project, stopping whatever else is running
+async fn start_project(app: &AppHandle, project_id: i32) -> Result<Option<TimeEntry>, String> {
+    let running = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+    if let Some(running) = running {
+        if running.project_id == project_id {
+            return Ok(Some(running));
+        }
+        stop_timer_with_feedback(app, running.id).await?;
+    }
+    start_timer_with_feedback(app, project_id, None).await.map(Some)
+}
+
+#[tauri::command]
+pub async fn palette_search(query: String) -> Result<Vec<PaletteItem>, String> {
+    Ok(palette_items(&query).await)
+}
+
+// Enter: start the selected item and dismiss the palette. On failure the palette
+// stays open so it can show the error.
+#[tauri::command]
+pub async fn palette_start(app: AppHandle, target: PaletteTarget) -> Result<Option<TimeEntry>, String> {
+    let started = match target {
+        PaletteTarget::Project { project_id } => start_project(&app, project_id).await?,
+        PaletteTarget::Template { template_id } => Some(templates::start(&app, template_id).await?),
+    };
+    hide(&app);
+    Ok(started)
+}
+
+// Shift+Enter: stop the running timer and dismiss the palette
+#[tauri::command]
+pub async fn palette_stop(app: AppHandle) -> Result<TimeEntry, String> {
+    let running = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+    let Some(running) = running else {
+        return Err("No timer is running".to_string());
+    };
+    let stopped = stop_timer_with_feedback(&app, running.id).await?;
+    hide(&app);
+    Ok(stopped)
+}
+
+#[tauri::command]
+pub async fn hide_palette(app: AppHandle) -> Result<(), String> {
+    hide(&app);
+    Ok(())
+}
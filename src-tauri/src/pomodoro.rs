@@ -0,0 +1,524 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::image::Image;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::notifications::{self, NotificationAction, NotificationCategory};
+use crate::preferences;
+use crate::{
+    format_timer_text, format_timestamp, parse_timestamp, project_label, rebuild_tray_menu,
+    refresh_tray_menu_data, render_tray_image, restart_timer_with_feedback, stop_timer_internal, TimeEntry,
+    ACTIVE_TIMER_STATE, LAST_TIMER_TEXT, TRAY_ICON,
+};
+
+// How often the countdown is redrawn and phase ends are checked
+const TICK_SECS: u64 = 1;
+
+// Oldest sessions are dropped beyond this many
+const MAX_STORED_SESSIONS: usize = 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PomodoroSettings {
+    // Start a pomodoro automatically whenever a timer starts
+    pub enabled: bool,
+    pub work_minutes: u32,
+    pub short_break_minutes: u32,
+    pub long_break_minutes: u32,
+    // Work sessions before a long break
+    pub sessions_before_long_break: u32,
+    // Stop the FreshTracks entry during breaks and restart it afterwards
+    pub auto_stop_during_breaks: bool,
+}
+
+impl Default for PomodoroSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            work_minutes: 25,
+            short_break_minutes: 5,
+            long_break_minutes: 15,
+            sessions_before_long_break: 4,
+            auto_stop_during_breaks: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PomodoroPhase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl PomodoroPhase {
+    pub fn label(self) -> &'static str {
+        match self {
+            PomodoroPhase::Work => "Focus",
+            PomodoroPhase::ShortBreak => "Short break",
+            PomodoroPhase::LongBreak => "Long break",
+        }
+    }
+}
+
+// The running pomodoro cycle
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroState {
+    pub phase: PomodoroPhase,
+    pub phase_started_at: String,
+    pub phase_ends_at: String,
+    // Work sessions completed in this cycle
+    pub completed_work_sessions: u32,
+    pub entry_id: i32,
+    pub project_id: i32,
+    // Entry stopped for the current break, restarted when it ends
+    pub stopped_for_break: bool,
+}
+
+// One finished (or interrupted) phase, persisted locally
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PomodoroSession {
+    pub phase: PomodoroPhase,
+    pub project_id: i32,
+    pub started_at: String,
+    pub ended_at: String,
+    pub completed: bool,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct PomodoroStats {
+    pub today_completed: u32,
+    pub today_focus_minutes: i64,
+    pub total_completed: u32,
+    pub total_focus_minutes: i64,
+    pub interrupted: u32,
+    pub sessions: Vec<PomodoroSession>,
+}
+
+static STATE: Lazy<Mutex<Option<PomodoroState>>> = Lazy::new(|| Mutex::new(None));
+static SESSIONS: Lazy<Mutex<Vec<PomodoroSession>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static SESSIONS_PATH: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
+
+// Load stored sessions from disk (pomodoro.json in the app config dir)
+pub fn load(app: &AppHandle) {
+    let path = match app.path().app_config_dir() {
+        Ok(dir) => dir.join("pomodoro.json"),
+        Err(e) => {
+            eprintln!("Failed to resolve config dir for pomodoro stats: {}", e);
+            return;
+        }
+    };
+
+    let sessions = match std::fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str::<Vec<PomodoroSession>>(&text).unwrap_or_else(|e| {
+            eprintln!("Invalid pomodoro stats file, starting fresh: {}", e);
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    };
+
+    *SESSIONS.lock().unwrap() = sessions;
+    *SESSIONS_PATH.lock().unwrap() = Some(path);
+}
+
+fn record_session(session: PomodoroSession) {
+    let sessions = {
+        let mut sessions = SESSIONS.lock().unwrap();
+        sessions.push(session);
+        if sessions.len() > MAX_STORED_SESSIONS {
+            let excess = sessions.len() - MAX_STORED_SESSIONS;
+            sessions.drain(..excess);
+        }
+        sessions.clone()
+    };
+
+    let path = SESSIONS_PATH.lock().unwrap().clone();
+    let Some(path) = path else {
+        return;
+    };
+    let result = path
+        .parent()
+        .map(std::fs::create_dir_all)
+        .unwrap_or(Ok(()))
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string_pretty(&sessions).map_err(|e| e.to_string()))
+        .and_then(|text| std::fs::write(&path, text).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        eprintln!("Failed to save pomodoro stats: {}", e);
+    }
+}
+
+pub fn is_active() -> bool {
+    STATE.lock().unwrap().is_some()
+}
+
+pub fn current_state() -> Option<PomodoroState> {
+    STATE.lock().unwrap().clone()
+}
+
+fn phase_minutes(settings: &PomodoroSettings, phase: PomodoroPhase) -> i64 {
+    let minutes = match phase {
+        PomodoroPhase::Work => settings.work_minutes,
+        PomodoroPhase::ShortBreak => settings.short_break_minutes,
+        PomodoroPhase::LongBreak => settings.long_break_minutes,
+    };
+    minutes.max(1) as i64
+}
+
+// The phase that follows `phase`, given work sessions completed so far (including this one)
+pub fn next_phase(settings: &PomodoroSettings, phase: PomodoroPhase, completed_work_sessions: u32) -> PomodoroPhase {
+    match phase {
+        PomodoroPhase::Work => {
+            let every = settings.sessions_before_long_break.max(1);
+            if completed_work_sessions % every == 0 {
+                PomodoroPhase::LongBreak
+            } else {
+                PomodoroPhase::ShortBreak
+            }
+        }
+        PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak => PomodoroPhase::Work,
+    }
+}
+
+fn begin(entry: &TimeEntry, now: DateTime<Utc>) -> PomodoroState {
+    let settings = preferences::get().pomodoro;
+    PomodoroState {
+        phase: PomodoroPhase::Work,
+        phase_started_at: format_timestamp(now),
+        phase_ends_at: format_timestamp(now + Duration::minutes(phase_minutes(&settings, PomodoroPhase::Work))),
+        completed_work_sessions: 0,
+        entry_id: entry.id,
+        project_id: entry.project_id,
+        stopped_for_break: false,
+    }
+}
+
+// Called whenever a timer starts; begins a cycle if pomodoro mode is on
+pub fn on_timer_started(app: &AppHandle, entry: &TimeEntry) {
+    let mut state = STATE.lock().unwrap();
+    match state.as_mut() {
+        // Resumed after an auto-stopped break, or switched to another entry mid-cycle
+        Some(current) => {
+            current.entry_id = entry.id;
+            current.project_id = entry.project_id;
+        }
+        None if preferences::get().pomodoro.enabled => {
+            *state = Some(begin(entry, Utc::now()));
+            drop(state);
+            let _ = app.emit("pomodoro-changed", current_state());
+        }
+        None => {}
+    }
+}
+
+// Called when the user stops or pauses the timer; a work phase in progress is interrupted
+pub fn on_timer_stopped(app: &AppHandle) {
+    let in_work = STATE.lock().unwrap().as_ref().is_some_and(|s| s.phase == PomodoroPhase::Work);
+    if in_work {
+        end(app);
+    }
+}
+
+// End the cycle, recording the current phase as interrupted
+fn end(app: &AppHandle) {
+    let Some(state) = STATE.lock().unwrap().take() else {
+        return;
+    };
+    record_session(PomodoroSession {
+        phase: state.phase,
+        project_id: state.project_id,
+        started_at: state.phase_started_at,
+        ended_at: format_timestamp(Utc::now()),
+        completed: false,
+    });
+    *LAST_TIMER_TEXT.lock().unwrap() = None;
+
+    // A running timer redraws its elapsed time on the next tick; otherwise drop the countdown
+    if ACTIVE_TIMER_STATE.lock().unwrap().is_none() {
+        if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
+            let _ = tray.set_tooltip(Some("FreshTracks - No active timer"));
+            let _ = tray.set_title(None::<&str>);
+            let icon_data = render_tray_image(None, false);
+            if let Ok(icon) = Image::from_bytes(&icon_data) {
+                let _ = tray.set_icon(Some(icon));
+            }
+        }
+    }
+
+    let _ = app.emit("pomodoro-changed", None::<PomodoroState>);
+    rebuild_tray_menu(app);
+}
+
+// Draw the remaining time in the tray; breaks render in grey
+fn show_countdown_in_tray(state: &PomodoroState, remaining_seconds: i64) {
+    let text = format_timer_text(remaining_seconds.max(0));
+    {
+        let mut last_text = LAST_TIMER_TEXT.lock().unwrap();
+        if last_text.as_ref() == Some(&text) {
+            return;
+        }
+        *last_text = Some(text.clone());
+    }
+
+    if let Some(tray) = TRAY_ICON.lock().unwrap().as_ref() {
+        let tooltip = format!("FreshTracks - {} ({} left)", state.phase.label(), text);
+        let _ = tray.set_tooltip(Some(&tooltip));
+
+        let image_data = render_tray_image(Some(&text), state.phase != PomodoroPhase::Work);
+        if let Ok(icon) = Image::from_bytes(&image_data) {
+            let _ = tray.set_icon(Some(icon));
+        }
+    }
+}
+
+// Drive the active cycle: redraw the countdown and switch phases when one runs out
+pub async fn run_pomodoro_loop(app: AppHandle) {
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(TICK_SECS)).await;
+
+        let Some(state) = current_state() else {
+            continue;
+        };
+
+        // A work phase can't continue once the timer was stopped elsewhere (e.g. the web app)
+        if state.phase == PomodoroPhase::Work && ACTIVE_TIMER_STATE.lock().unwrap().is_none() {
+            end(&app);
+            continue;
+        }
+
+        let now = Utc::now();
+        let Some(ends_at) = parse_timestamp(&state.phase_ends_at) else {
+            end(&app);
+            continue;
+        };
+
+        if now < ends_at {
+            show_countdown_in_tray(&state, (ends_at - now).num_seconds());
+        } else {
+            advance(&app, state, now).await;
+        }
+    }
+}
+
+// Finish the current phase and start the next one
+async fn advance(app: &AppHandle, state: PomodoroState, now: DateTime<Utc>) {
+    let settings = preferences::get().pomodoro;
+
+    record_session(PomodoroSession {
+        phase: state.phase,
+        project_id: state.project_id,
+        started_at: state.phase_started_at.clone(),
+        ended_at: format_timestamp(now),
+        completed: true,
+    });
+
+    let completed = state.completed_work_sessions + u32::from(state.phase == PomodoroPhase::Work);
+    let next = next_phase(&settings, state.phase, completed);
+    let mut stopped_for_break = false;
+    let mut entry_id = state.entry_id;
+
+    match next {
+        PomodoroPhase::ShortBreak | PomodoroPhase::LongBreak if settings.auto_stop_during_breaks => {
+            let running = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+            if let Some(timer) = running {
+                match stop_timer_internal(timer.id).await {
+                    Ok(_) => {
+                        *ACTIVE_TIMER_STATE.lock().unwrap() = None;
+                        stopped_for_break = true;
+                    }
+                    Err(e) => eprintln!("Failed to stop timer for pomodoro break: {}", e),
+                }
+            }
+        }
+        // Only pick the entry back up if nothing else was started during the break. This goes
+        // through the normal start path so the tray, the UI and RPC subscribers all hear of it.
+        PomodoroPhase::Work if state.stopped_for_break && ACTIVE_TIMER_STATE.lock().unwrap().is_none() => {
+            match restart_timer_with_feedback(app, state.entry_id).await {
+                Ok(entry) => entry_id = entry.id,
+                Err(e) => eprintln!("Failed to restart timer after pomodoro break: {}", e),
+            }
+        }
+        _ => {}
+    }
+
+    let next_state = PomodoroState {
+        phase: next,
+        phase_started_at: format_timestamp(now),
+        phase_ends_at: format_timestamp(now + Duration::minutes(phase_minutes(&settings, next))),
+        completed_work_sessions: completed,
+        entry_id,
+        project_id: state.project_id,
+        stopped_for_break,
+    };
+    *STATE.lock().unwrap() = Some(next_state.clone());
+    *LAST_TIMER_TEXT.lock().unwrap() = None;
+
+    let project = ACTIVE_TIMER_STATE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map(|t| project_label(t.project.as_ref()));
+    let (title, body) = match next {
+        PomodoroPhase::Work => (
+            "Back to work".to_string(),
+            format!("{} minutes of focus{}", phase_minutes(&settings, next), project.map(|p| format!(" on {}", p)).unwrap_or_default()),
+        ),
+        _ => (
+            format!("Time for a {}", next.label().to_lowercase()),
+            format!("{} pomodoros done. Back in {} minutes", completed, phase_minutes(&settings, next)),
+        ),
+    };
+    notifications::notify(app, NotificationCategory::Pomodoro, &title, &body, &[NotificationAction::Open]);
+
+    let _ = app.emit("pomodoro-changed", Some(next_state));
+    // A restart after the break already announced itself
+    if stopped_for_break {
+        let _ = app.emit("timer-toggled", ());
+        refresh_tray_menu_data(app).await;
+    }
+}
+
+pub fn stats(sessions: &[PomodoroSession], now: DateTime<Utc>) -> PomodoroStats {
+    let today = now.with_timezone(&Local).date_naive();
+    let focus_minutes = |s: &PomodoroSession| match (parse_timestamp(&s.started_at), parse_timestamp(&s.ended_at)) {
+        (Some(start), Some(end)) => (end - start).num_minutes(),
+        _ => 0,
+    };
+    let is_today = |s: &PomodoroSession| {
+        parse_timestamp(&s.started_at).is_some_and(|dt| dt.with_timezone(&Local).date_naive() == today)
+    };
+
+    let work: Vec<&PomodoroSession> = sessions.iter().filter(|s| s.phase == PomodoroPhase::Work).collect();
+    let completed: Vec<&PomodoroSession> = work.iter().copied().filter(|s| s.completed).collect();
+
+    PomodoroStats {
+        today_completed: completed.iter().filter(|s| is_today(s)).count() as u32,
+        today_focus_minutes: work.iter().filter(|s| is_today(s)).map(|s| focus_minutes(s)).sum(),
+        total_completed: completed.len() as u32,
+        total_focus_minutes: work.iter().map(|s| focus_minutes(s)).sum(),
+        interrupted: (work.len() - completed.len()) as u32,
+        sessions: sessions.to_vec(),
+    }
+}
+
+// Start a cycle on the running timer (regardless of the automatic setting)
+pub fn start(app: &AppHandle) -> Result<PomodoroState, String> {
+    let timer = ACTIVE_TIMER_STATE
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "Start a timer before starting a pomodoro".to_string())?;
+
+    let state = {
+        let mut current = STATE.lock().unwrap();
+        if let Some(existing) = current.as_ref() {
+            return Ok(existing.clone());
+        }
+        let state = begin(&timer, Utc::now());
+        *current = Some(state.clone());
+        state
+    };
+
+    let _ = app.emit("pomodoro-changed", Some(state.clone()));
+    rebuild_tray_menu(app);
+    Ok(state)
+}
+
+#[tauri::command]
+pub async fn start_pomodoro(app: AppHandle) -> Result<PomodoroState, String> {
+    start(&app)
+}
+
+#[tauri::command]
+pub async fn stop_pomodoro(app: AppHandle) -> Result<(), String> {
+    end(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_pomodoro_state() -> Result<Option<PomodoroState>, String> {
+    Ok(current_state())
+}
+
+#[tauri::command]
+pub async fn get_pomodoro_stats() -> Result<PomodoroStats, String> {
+    Ok(stats(&SESSIONS.lock().unwrap(), Utc::now()))
+}
+
+#[tauri::command]
+pub async fn set_pomodoro_settings(settings: PomodoroSettings) -> Result<PomodoroSettings, String> {
+    let prefs = preferences::update(|prefs| prefs.pomodoro = settings)?;
+    Ok(prefs.pomodoro)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2024, 6, day, hour, minute, 0).unwrap().with_timezone(&Utc)
+    }
+
+    fn session(phase: PomodoroPhase, start: DateTime<Utc>, end: DateTime<Utc>, completed: bool) -> PomodoroSession {
+        PomodoroSession {
+            phase,
+            project_id: 1,
+            started_at: format_timestamp(start),
+            ended_at: format_timestamp(end),
+            completed,
+        }
+    }
+
+    #[test]
+    fn long_break_every_n_work_sessions() {
+        let settings = PomodoroSettings::default();
+        let after_work: Vec<PomodoroPhase> = (1..=8).map(|n| next_phase(&settings, PomodoroPhase::Work, n)).collect();
+        assert_eq!(after_work, vec![
+            PomodoroPhase::ShortBreak,
+            PomodoroPhase::ShortBreak,
+            PomodoroPhase::ShortBreak,
+            PomodoroPhase::LongBreak,
+            PomodoroPhase::ShortBreak,
+            PomodoroPhase::ShortBreak,
+            PomodoroPhase::ShortBreak,
+            PomodoroPhase::LongBreak,
+        ]);
+    }
+
+    #[test]
+    fn breaks_are_followed_by_work() {
+        let settings = PomodoroSettings::default();
+        assert_eq!(next_phase(&settings, PomodoroPhase::ShortBreak, 1), PomodoroPhase::Work);
+        assert_eq!(next_phase(&settings, PomodoroPhase::LongBreak, 4), PomodoroPhase::Work);
+    }
+
+    #[test]
+    fn zero_cadence_means_a_long_break_every_time() {
+        let settings = PomodoroSettings { sessions_before_long_break: 0, ..Default::default() };
+        assert_eq!(next_phase(&settings, PomodoroPhase::Work, 1), PomodoroPhase::LongBreak);
+    }
+
+    #[test]
+    fn daily_stats_count_sessions_by_their_start_day() {
+        let sessions = vec![
+            // Started before midnight, so it belongs to yesterday
+            session(PomodoroPhase::Work, local(4, 23, 50), local(5, 0, 15), true),
+            session(PomodoroPhase::Work, local(5, 9, 0), local(5, 9, 25), true),
+            session(PomodoroPhase::ShortBreak, local(5, 9, 25), local(5, 9, 30), true),
+            session(PomodoroPhase::Work, local(5, 9, 30), local(5, 9, 40), false),
+        ];
+        let stats = stats(&sessions, local(5, 12, 0));
+
+        assert_eq!(stats.today_completed, 1);
+        assert_eq!(stats.today_focus_minutes, 35);
+        assert_eq!(stats.total_completed, 2);
+        assert_eq!(stats.total_focus_minutes, 60);
+        assert_eq!(stats.interrupted, 1);
+        assert_eq!(stats.sessions.len(), 4);
+    }
+}
@@ -0,0 +1,384 @@
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+
+use crate::idle;
+use crate::notifications::{self, NotificationAction, NotificationCategory};
+use crate::preferences;
+use crate::{
+    format_timestamp, parse_timestamp, refresh_tray_menu_data, show_main_window, update_entry_internal,
+    TimeEntry, UpdateEntryData, ACTIVE_TIMER_STATE, WS_RECONNECT,
+};
+
+// How often the power monitor ticks
+const TICK_SECS: u64 = 5;
+
+// A wall-clock jump this much larger than the monotonic clock means the machine slept
+const SLEEP_GAP_SECS: i64 = 30;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct PowerSettings {
+    // Offer to subtract sleep time from a running timer on wake
+    pub ask_subtract_sleep: bool,
+    // Ignore shorter absences
+    pub min_away_minutes: u32,
+    // Treat a locked screen like sleep
+    pub include_screen_lock: bool,
+}
+
+impl Default for PowerSettings {
+    fn default() -> Self {
+        Self {
+            ask_subtract_sleep: true,
+            min_away_minutes: 5,
+            include_screen_lock: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PowerEvent {
+    Wake { slept_at: String, woke_at: String },
+    ScreenLocked { at: String },
+    ScreenUnlocked { locked_at: String, unlocked_at: String },
+}
+
+// Time away (sleep or locked screen) while a timer was running, waiting for the user's decision
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AwayPrompt {
+    pub entry: TimeEntry,
+    pub away_started_at: String,
+    pub away_ended_at: String,
+    pub away_minutes: i64,
+}
+
+static PENDING_PROMPT: Lazy<Mutex<Option<AwayPrompt>>> = Lazy::new(|| Mutex::new(None));
+
+// Screen lock state from the current login session
+#[cfg(target_os = "macos")]
+fn is_screen_locked() -> Option<bool> {
+    use std::ffi::c_void;
+    type CFTypeRef = *const c_void;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGSessionCopyCurrentDictionary() -> CFTypeRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFDictionaryGetValue(dict: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+        fn CFStringCreateWithCString(alloc: CFTypeRef, c_str: *const std::ffi::c_char, encoding: u32) -> CFTypeRef;
+        fn CFBooleanGetValue(boolean: CFTypeRef) -> u8;
+        fn CFRelease(cf: CFTypeRef);
+    }
+
+    const UTF8_ENCODING: u32 = 0x0800_0100;
+
+    unsafe {
+        let session = CGSessionCopyCurrentDictionary();
+        if session.is_null() {
+            return None;
+        }
+        let key = CFStringCreateWithCString(std::ptr::null(), c"CGSSessionScreenIsLocked".as_ptr(), UTF8_ENCODING);
+        let value = CFDictionaryGetValue(session, key);
+        // The key is only present while the screen is locked
+        let locked = !value.is_null() && CFBooleanGetValue(value) != 0;
+        CFRelease(key);
+        CFRelease(session);
+        Some(locked)
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_screen_locked() -> Option<bool> {
+    None
+}
+
+// Pure sleep detector, fed one wall-clock and monotonic reading per tick. The monotonic
+// clock stops while the machine sleeps, so the wall clock running ahead of it means a sleep.
+#[derive(Debug)]
+pub struct SleepDetector {
+    last_wall: DateTime<Utc>,
+    last_mono: std::time::Duration,
+}
+
+impl SleepDetector {
+    pub fn new(wall: DateTime<Utc>, mono: std::time::Duration) -> Self {
+        Self { last_wall: wall, last_mono: mono }
+    }
+
+    // Returns (slept_at, woke_at) when the machine slept since the previous reading
+    pub fn observe(&mut self, wall: DateTime<Utc>, mono: std::time::Duration) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+        let wall_elapsed = (wall - self.last_wall).num_seconds();
+        let mono_elapsed = mono.saturating_sub(self.last_mono).as_secs() as i64;
+        let last_wall = self.last_wall;
+        self.last_wall = wall;
+        self.last_mono = mono;
+
+        if wall_elapsed - mono_elapsed > SLEEP_GAP_SECS {
+            Some((last_wall + Duration::seconds(mono_elapsed), wall))
+        } else {
+            None
+        }
+    }
+}
+
+// Detect sleep/wake by comparing wall-clock and monotonic time and poll the screen lock state
+pub async fn run_power_monitor(app: AppHandle) {
+    let started = Instant::now();
+    let mut detector = SleepDetector::new(Utc::now(), started.elapsed());
+    let mut locked_since: Option<DateTime<Utc>> = None;
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(TICK_SECS)).await;
+
+        let now_wall = Utc::now();
+        if let Some((slept_at, woke_at)) = detector.observe(now_wall, started.elapsed()) {
+            handle_power_event(&app, PowerEvent::Wake {
+                slept_at: format_timestamp(slept_at),
+                woke_at: format_timestamp(woke_at),
+            }).await;
+        }
+
+        match (is_screen_locked(), locked_since) {
+            (Some(true), None) => {
+                locked_since = Some(now_wall);
+                handle_power_event(&app, PowerEvent::ScreenLocked { at: format_timestamp(now_wall) }).await;
+            }
+            (Some(false), Some(since)) => {
+                locked_since = None;
+                handle_power_event(&app, PowerEvent::ScreenUnlocked {
+                    locked_at: format_timestamp(since),
+                    unlocked_at: format_timestamp(now_wall),
+                }).await;
+            }
+            _ => {}
+        }
+    }
+}
+
+async fn handle_power_event(app: &AppHandle, event: PowerEvent) {
+    println!("Power event: {:?}", event);
+    let _ = app.emit("power-event", event.clone());
+
+    let settings = preferences::get().power;
+    let away = match &event {
+        PowerEvent::Wake { slept_at, woke_at } => {
+            // The socket is likely half-dead after sleep; force a fresh connection
+            WS_RECONNECT.notify_waiters();
+            // Woken to a locked screen: the unlock will cover the whole absence
+            if settings.include_screen_lock && is_screen_locked() == Some(true) {
+                refresh_tray_menu_data(app).await;
+                return;
+            }
+            Some((slept_at.clone(), woke_at.clone()))
+        }
+        PowerEvent::ScreenUnlocked { locked_at, unlocked_at } if settings.include_screen_lock => {
+            Some((locked_at.clone(), unlocked_at.clone()))
+        }
+        _ => None,
+    };
+    let Some((away_started_at, away_ended_at)) = away else {
+        return;
+    };
+
+    // Refresh the running timer so the tray and UI don't show stale elapsed time
+    refresh_tray_menu_data(app).await;
+    let _ = app.emit("timer-toggled", ());
+
+    let timer = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+    let (Some(entry), true) = (timer, settings.ask_subtract_sleep) else {
+        return;
+    };
+
+    let away_minutes = match (parse_timestamp(&away_started_at), parse_timestamp(&away_ended_at)) {
+        (Some(start), Some(end)) => (end - start).num_minutes(),
+        _ => return,
+    };
+    if away_minutes < settings.min_away_minutes as i64 {
+        return;
+    }
+
+    let prompt = AwayPrompt {
+        entry,
+        away_started_at,
+        away_ended_at,
+        away_minutes,
+    };
+    // Only one prompt may cover the absence, or answering both subtracts it twice
+    idle::clear_pending_prompt();
+    *PENDING_PROMPT.lock().unwrap() = Some(prompt.clone());
+
+    notifications::notify(
+        app,
+        NotificationCategory::Away,
+        "Welcome back",
+        &format!("You were away for {}m with a timer running. Subtract it?", away_minutes),
+        &[NotificationAction::Open],
+    );
+
+    show_main_window(app);
+    let _ = app.emit("away-detected", prompt);
+}
+
+// Forget the away period once an idle prompt covers it
+pub fn clear_pending_prompt() {
+    *PENDING_PROMPT.lock().unwrap() = None;
+}
+
+#[tauri::command]
+pub async fn get_away_prompt() -> Result<Option<AwayPrompt>, String> {
+    Ok(PENDING_PROMPT.lock().unwrap().clone())
+}
+
+// Update that stops the time away from counting. Elapsed time runs from the current
+// session's start (resumed_at once resumed, started_at otherwise), so that start moves
+// forward by the part of the absence that falls inside the session. None if nothing overlaps.
+pub fn subtract_away(entry: &TimeEntry, away_start: DateTime<Utc>, away_end: DateTime<Utc>) -> Option<UpdateEntryData> {
+    let session_start = parse_timestamp(entry.resumed_at.as_deref().unwrap_or(&entry.started_at))?;
+    let overlap = away_end - away_start.max(session_start);
+    if overlap <= Duration::zero() {
+        return None;
+    }
+    let new_session_start = format_timestamp(session_start + overlap);
+
+    let (started_at, resumed_at) = match entry.resumed_at {
+        Some(_) => (entry.started_at.clone(), Some(new_session_start)),
+        None => (new_session_start, None),
+    };
+    Some(UpdateEntryData {
+        project_id: entry.project_id,
+        description: entry.description.clone(),
+        started_at,
+        stopped_at: None,
+        is_billable: entry.is_billable,
+        resumed_at,
+    })
+}
+
+// Apply the user's choice; subtracting moves the running session's start past the time away
+#[tauri::command]
+pub async fn resolve_away(app: AppHandle, subtract: bool) -> Result<Option<TimeEntry>, String> {
+    // Stays pending until the change is applied so a failed attempt can be retried
+    let prompt = PENDING_PROMPT
+        .lock()
+        .unwrap()
+        .clone()
+        .ok_or_else(|| "No away period is pending".to_string())?;
+
+    let away = parse_timestamp(&prompt.away_started_at).zip(parse_timestamp(&prompt.away_ended_at));
+    let update = match (subtract, away) {
+        (false, _) => None,
+        (true, Some((start, end))) => subtract_away(&prompt.entry, start, end),
+        (true, None) => return Err("The away period has invalid times".to_string()),
+    };
+    let Some(update) = update else {
+        *PENDING_PROMPT.lock().unwrap() = None;
+        return Ok(None);
+    };
+
+    let updated = update_entry_internal(prompt.entry.id, update).await?;
+    *PENDING_PROMPT.lock().unwrap() = None;
+
+    *ACTIVE_TIMER_STATE.lock().unwrap() = Some(updated.clone());
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(&app).await;
+
+    Ok(Some(updated))
+}
+
+#[tauri::command]
+pub async fn set_power_settings(settings: PowerSettings) -> Result<PowerSettings, String> {
+    let prefs = preferences::update(|prefs| prefs.power = settings)?;
+    Ok(prefs.power)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::Duration as MonoDuration;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap()
+    }
+
+    fn running(started_at: DateTime<Utc>, resumed_at: Option<DateTime<Utc>>) -> TimeEntry {
+        TimeEntry {
+            id: 7,
+            project_id: 3,
+            project: None,
+            description: Some("Review".to_string()),
+            started_at: format_timestamp(started_at),
+            stopped_at: None,
+            resumed_at: resumed_at.map(format_timestamp),
+            duration_minutes: resumed_at.map(|_| 30.0),
+            is_billable: true,
+        }
+    }
+
+    // Replays (wall, monotonic seconds) readings, one per tick, collecting detected sleeps
+    fn replay(readings: &[(DateTime<Utc>, u64)]) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let (first_wall, first_mono) = readings[0];
+        let mut detector = SleepDetector::new(first_wall, MonoDuration::from_secs(first_mono));
+        readings[1..]
+            .iter()
+            .filter_map(|&(wall, mono)| detector.observe(wall, MonoDuration::from_secs(mono)))
+            .collect()
+    }
+
+    #[test]
+    fn clocks_in_step_are_not_sleep() {
+        let start = at(10, 0);
+        let readings: Vec<(DateTime<Utc>, u64)> = (0..4)
+            .map(|i| (start + Duration::seconds(i * TICK_SECS as i64), i as u64 * TICK_SECS))
+            .collect();
+        assert!(replay(&readings).is_empty());
+    }
+
+    #[test]
+    fn wall_clock_jump_is_sleep() {
+        // Slept 5s after the 10:00 tick; the monotonic clock only saw those 5s
+        let readings = [(at(10, 0), 0), (at(10, 40), 5), (at(10, 40) + Duration::seconds(5), 10)];
+        assert_eq!(replay(&readings), vec![(at(10, 0) + Duration::seconds(5), at(10, 40))]);
+    }
+
+    #[test]
+    fn small_drift_is_ignored() {
+        let readings = [(at(10, 0), 0), (at(10, 0) + Duration::seconds(5 + SLEEP_GAP_SECS), 5)];
+        assert!(replay(&readings).is_empty());
+    }
+
+    #[test]
+    fn subtract_moves_start_of_unresumed_entry() {
+        let entry = running(at(9, 0), None);
+        let update = subtract_away(&entry, at(10, 0), at(10, 20)).unwrap();
+        assert_eq!(update.started_at, format_timestamp(at(9, 20)));
+        assert_eq!(update.resumed_at, None);
+        assert_eq!(update.stopped_at, None);
+    }
+
+    #[test]
+    fn subtract_moves_resume_time_of_resumed_entry() {
+        let entry = running(at(9, 0), Some(at(11, 0)));
+        let update = subtract_away(&entry, at(11, 30), at(11, 45)).unwrap();
+        assert_eq!(update.started_at, entry.started_at);
+        assert_eq!(update.resumed_at, Some(format_timestamp(at(11, 15))));
+    }
+
+    #[test]
+    fn subtract_counts_only_time_inside_the_session() {
+        // Away 10:50–11:10 but the session only began at 11:00
+        let entry = running(at(9, 0), Some(at(11, 0)));
+        let update = subtract_away(&entry, at(10, 50), at(11, 10)).unwrap();
+        assert_eq!(update.resumed_at, Some(format_timestamp(at(11, 10))));
+
+        assert!(subtract_away(&entry, at(10, 0), at(10, 30)).is_none());
+    }
+}
@@ -0,0 +1,198 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+use crate::http_api::HttpApiSettings;
+use crate::idle::IdleSettings;
+use crate::notifications::NotificationSettings;
+use crate::pomodoro::PomodoroSettings;
+use crate::power::PowerSettings;
+use crate::quick_start::QuickStartPolicy;
+use crate::reminders::ReminderSettings;
+use crate::reports::ReportSettings;
+use crate::shortcuts::{default_bindings, same_accelerator, ShortcutAction, ShortcutBinding};
+use crate::templates::TimerTemplate;
+use crate::validation::ValidationSettings;
+
+// Local widget preferences (persisted as JSON in the app config dir).
+// Unlike UserSettings, these never leave this machine.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct Preferences {
+    pub shortcuts: Vec<ShortcutBinding>,
+    // Default shortcut actions already added once, so ones the user unbinds stay unbound.
+    // Missing from older files, which had already offered the defaults of their time.
+    #[serde(default = "legacy_offered_shortcuts")]
+    pub offered_shortcuts: Vec<ShortcutAction>,
+    pub quick_start: QuickStartPolicy,
+    pub idle: IdleSettings,
+    pub power: PowerSettings,
+    pub reminders: ReminderSettings,
+    pub notifications: NotificationSettings,
+    pub pomodoro: PomodoroSettings,
+    pub reports: ReportSettings,
+    pub validation: ValidationSettings,
+    pub templates: Vec<TimerTemplate>,
+    pub http_api: HttpApiSettings,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Self {
+            shortcuts: default_bindings(),
+            offered_shortcuts: default_bindings().into_iter().map(|b| b.action).collect(),
+            quick_start: QuickStartPolicy::default(),
+            idle: IdleSettings::default(),
+            power: PowerSettings::default(),
+            reminders: ReminderSettings::default(),
+            notifications: NotificationSettings::default(),
+            pomodoro: PomodoroSettings::default(),
+            reports: ReportSettings::default(),
+            validation: ValidationSettings::default(),
+            templates: Vec::new(),
+            http_api: HttpApiSettings::default(),
+        }
+    }
+}
+
+static PREFERENCES: Lazy<Mutex<Preferences>> = Lazy::new(|| Mutex::new(Preferences::default()));
+static PREFERENCES_PATH: Lazy<Mutex<Option<PathBuf>>> = Lazy::new(|| Mutex::new(None));
+
+// Load preferences from disk, falling back to defaults if missing or invalid
+pub fn load(app: &AppHandle) {
+    let path = match app.path().app_config_dir() {
+        Ok(dir) => dir.join("preferences.json"),
+        Err(e) => {
+            eprintln!("Failed to resolve config dir for preferences: {}", e);
+            return;
+        }
+    };
+
+    let mut prefs = match std::fs::read_to_string(&path) {
+        Ok(text) => serde_json::from_str::<Preferences>(&text).unwrap_or_else(|e| {
+            eprintln!("Invalid preferences file, using defaults: {}", e);
+            Preferences::default()
+        }),
+        Err(_) => Preferences::default(),
+    };
+    let merged = merge_new_default_shortcuts(&mut prefs);
+
+    *PREFERENCES.lock().unwrap() = prefs.clone();
+    *PREFERENCES_PATH.lock().unwrap() = Some(path);
+
+    if merged {
+        if let Err(e) = save(&prefs) {
+            eprintln!("Failed to save new default shortcuts: {}", e);
+        }
+    }
+}
+
+// Default shortcut actions that existed before offered_shortcuts was saved. An older
+// file already offered these, so one the user unbound there stays unbound.
+fn legacy_offered_shortcuts() -> Vec<ShortcutAction> {
+    vec![ShortcutAction::ToggleTimer]
+}
+
+// Add default shortcuts introduced since the preferences were saved, skipping actions
+// that are already bound and keys that are taken. Returns whether anything changed.
+fn merge_new_default_shortcuts(prefs: &mut Preferences) -> bool {
+    let mut changed = false;
+    for binding in default_bindings() {
+        if prefs.offered_shortcuts.contains(&binding.action) {
+            continue;
+        }
+        prefs.offered_shortcuts.push(binding.action);
+        changed = true;
+
+        let taken = prefs
+            .shortcuts
+            .iter()
+            .any(|b| b.action == binding.action || same_accelerator(&b.accelerator, &binding.accelerator));
+        if !taken {
+            prefs.shortcuts.push(binding);
+        }
+    }
+    changed
+}
+
+// Snapshot of the current preferences
+pub fn get() -> Preferences {
+    PREFERENCES.lock().unwrap().clone()
+}
+
+// Apply a change to the preferences and persist it
+pub fn update<F: FnOnce(&mut Preferences)>(f: F) -> Result<Preferences, String> {
+    let updated = {
+        let mut prefs = PREFERENCES.lock().unwrap();
+        f(&mut prefs);
+        prefs.clone()
+    };
+    save(&updated)?;
+    Ok(updated)
+}
+
+fn save(prefs: &Preferences) -> Result<(), String> {
+    let path = PREFERENCES_PATH.lock().unwrap().clone();
+    let Some(path) = path else {
+        return Err("Preferences have not been loaded".to_string());
+    };
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    let text = serde_json::to_string_pretty(prefs).map_err(|e| e.to_string())?;
+    std::fs::write(&path, text).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_preferences() -> Result<Preferences, String> {
+    Ok(get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn actions(prefs: &Preferences) -> Vec<ShortcutAction> {
+        prefs.shortcuts.iter().map(|b| b.action).collect()
+    }
+
+    #[test]
+    fn older_file_gets_new_default_shortcuts() {
+        let json = r#"{"shortcuts": [{"action": "toggle_timer", "accelerator": "Super+Shift+T"}]}"#;
+        let mut prefs: Preferences = serde_json::from_str(json).unwrap();
+
+        assert!(merge_new_default_shortcuts(&mut prefs));
+        assert_eq!(actions(&prefs), vec![ShortcutAction::ToggleTimer, ShortcutAction::OpenPalette]);
+        assert!(!merge_new_default_shortcuts(&mut prefs));
+    }
+
+    #[test]
+    fn unbound_default_stays_unbound() {
+        let mut prefs = Preferences::default();
+        prefs.shortcuts.retain(|b| b.action != ShortcutAction::OpenPalette);
+
+        assert!(!merge_new_default_shortcuts(&mut prefs));
+        assert_eq!(actions(&prefs), vec![ShortcutAction::ToggleTimer]);
+    }
+
+    #[test]
+    fn taken_key_is_not_reassigned() {
+        let json = r#"{"shortcuts": [{"action": "toggle_window", "accelerator": "super+shift+space"}]}"#;
+        let mut prefs: Preferences = serde_json::from_str(json).unwrap();
+
+        assert!(merge_new_default_shortcuts(&mut prefs));
+        assert_eq!(actions(&prefs), vec![ShortcutAction::ToggleWindow]);
+    }
+
+    #[test]
+    fn older_file_keeps_unbound_toggle_timer_unbound() {
+        let json = r#"{"shortcuts": []}"#;
+        let mut prefs: Preferences = serde_json::from_str(json).unwrap();
+
+        assert!(merge_new_default_shortcuts(&mut prefs));
+        assert_eq!(actions(&prefs), vec![ShortcutAction::OpenPalette]);
+    }
+}
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Datelike, Duration, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::preferences;
+use crate::{entry_elapsed_seconds, last_stopped_entry, parse_timestamp, TimeEntry};
+
+// How the toggle shortcut picks what to start when no timer is running
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum QuickStartPolicy {
+    // Restart the most recently stopped entry (keeps its description)
+    #[default]
+    LastStopped,
+    // Always start a new timer on a fixed project
+    PinnedProject { project_id: i32 },
+    // Start a new timer on the project with the most tracked time this week
+    MostUsedThisWeek,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QuickStartTarget {
+    RestartEntry(i32),
+    StartProject(i32),
+}
+
+// Choose the quick-start target for a policy, falling back to the last stopped
+// entry and then to `fallback_project` when the policy has nothing to offer
+pub fn choose_target(
+    policy: &QuickStartPolicy,
+    entries: &[TimeEntry],
+    now: DateTime<Utc>,
+    fallback_project: Option<i32>,
+) -> Option<QuickStartTarget> {
+    let chosen = match policy {
+        QuickStartPolicy::LastStopped => None,
+        QuickStartPolicy::PinnedProject { project_id } => Some(QuickStartTarget::StartProject(*project_id)),
+        QuickStartPolicy::MostUsedThisWeek => most_used_project_this_week(entries, now).map(QuickStartTarget::StartProject),
+    };
+
+    chosen
+        .or_else(|| last_stopped_entry(entries).map(|e| QuickStartTarget::RestartEntry(e.id)))
+        .or_else(|| fallback_project.map(QuickStartTarget::StartProject))
+}
+
+// Project with the most tracked time since Monday (local time)
+fn most_used_project_this_week(entries: &[TimeEntry], now: DateTime<Utc>) -> Option<i32> {
+    let today = now.with_timezone(&Local).date_naive();
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
+    let mut totals: HashMap<i32, i64> = HashMap::new();
+    for entry in entries {
+        let in_week = parse_timestamp(&entry.started_at)
+            .map(|dt| dt.with_timezone(&Local).date_naive() >= week_start)
+            .unwrap_or(false);
+        if in_week {
+            *totals.entry(entry.project_id).or_insert(0) += entry_elapsed_seconds(entry, now);
+        }
+    }
+
+    totals
+        .into_iter()
+        .max_by_key(|(project_id, secs)| (*secs, -project_id))
+        .map(|(project_id, _)| project_id)
+}
+
+#[tauri::command]
+pub async fn set_quick_start_policy(policy: QuickStartPolicy) -> Result<QuickStartPolicy, String> {
+    let prefs = preferences::update(|prefs| prefs.quick_start = policy)?;
+    Ok(prefs.quick_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format_timestamp, Project};
+    use chrono::TimeZone;
+
+    // Wednesday 5 June 2024; the week started on Monday the 3rd
+    fn now() -> DateTime<Utc> {
+        local(5, 12, 0)
+    }
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2024, 6, day, hour, minute, 0).unwrap().with_timezone(&Utc)
+    }
+
+    fn entry(id: i32, project_id: i32, start: DateTime<Utc>, hours: Option<i64>) -> TimeEntry {
+        let end = hours.map(|h| start + Duration::hours(h));
+        TimeEntry {
+            id,
+            project_id,
+            project: Some(Project { id: project_id, name: format!("Project {}", project_id), client: None }),
+            description: None,
+            started_at: format_timestamp(start),
+            stopped_at: end.map(format_timestamp),
+            resumed_at: None,
+            duration_minutes: end.map(|end| (end - start).num_minutes() as f64),
+            is_billable: true,
+        }
+    }
+
+    fn history() -> Vec<TimeEntry> {
+        vec![
+            entry(1, 1, local(3, 9, 0), Some(2)),
+            entry(2, 2, local(5, 9, 0), Some(1)),
+            // Last week, so it doesn't count towards this week's totals
+            entry(3, 3, local(1, 9, 0), Some(5)),
+        ]
+    }
+
+    #[test]
+    fn last_stopped_restarts_the_latest_stopped_entry() {
+        let mut entries = history();
+        entries.push(entry(4, 4, local(5, 11, 0), None));
+
+        let target = choose_target(&QuickStartPolicy::LastStopped, &entries, now(), Some(9));
+        assert_eq!(target, Some(QuickStartTarget::RestartEntry(2)));
+    }
+
+    #[test]
+    fn pinned_project_always_starts_that_project() {
+        let policy = QuickStartPolicy::PinnedProject { project_id: 7 };
+        assert_eq!(choose_target(&policy, &history(), now(), Some(9)), Some(QuickStartTarget::StartProject(7)));
+        assert_eq!(choose_target(&policy, &[], now(), None), Some(QuickStartTarget::StartProject(7)));
+    }
+
+    #[test]
+    fn most_used_counts_only_this_week() {
+        let target = choose_target(&QuickStartPolicy::MostUsedThisWeek, &history(), now(), Some(9));
+        assert_eq!(target, Some(QuickStartTarget::StartProject(1)));
+    }
+
+    #[test]
+    fn most_used_falls_back_to_last_stopped_without_time_this_week() {
+        let entries = vec![entry(3, 3, local(1, 9, 0), Some(5))];
+        let target = choose_target(&QuickStartPolicy::MostUsedThisWeek, &entries, now(), Some(9));
+        assert_eq!(target, Some(QuickStartTarget::RestartEntry(3)));
+    }
+
+    #[test]
+    fn empty_history_uses_the_fallback_project() {
+        for policy in [QuickStartPolicy::LastStopped, QuickStartPolicy::MostUsedThisWeek] {
+            assert_eq!(choose_target(&policy, &[], now(), Some(9)), Some(QuickStartTarget::StartProject(9)));
+            assert_eq!(choose_target(&policy, &[], now(), None), None);
+        }
+    }
+
+    #[test]
+    fn entries_on_an_archived_project_still_count() {
+        // Without the embedded project (e.g. archived) the entry still counts by its project id
+        let mut entries = history();
+        entries[0].project = None;
+
+        let target = choose_target(&QuickStartPolicy::MostUsedThisWeek, &entries, now(), Some(9));
+        assert_eq!(target, Some(QuickStartTarget::StartProject(1)));
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, Utc, Weekday};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+use crate::notifications::{self, NotificationAction, NotificationCategory};
+use crate::preferences;
+use crate::{entry_elapsed_seconds, format_duration_minutes, project_label, TimeEntry, ACTIVE_TIMER_STATE, PAUSED_TIMER};
+
+// How often reminder rules are evaluated
+const CHECK_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReminderRule {
+    // A timer has been running longer than `hours`
+    LongRunning { hours: f64 },
+    // A timer is still running after `time` ("HH:MM", local)
+    PastEndOfDay { time: String },
+    // No timer has run for `grace_minutes` between `start` and `end` ("HH:MM", local)
+    NoTimerDuringWorkingHours {
+        start: String,
+        end: String,
+        weekdays_only: bool,
+        grace_minutes: u32,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ReminderSettings {
+    pub enabled: bool,
+    // Minimum time between repeats of the same reminder
+    pub repeat_minutes: u32,
+    pub rules: Vec<ReminderRule>,
+}
+
+impl Default for ReminderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            repeat_minutes: 60,
+            rules: vec![
+                ReminderRule::LongRunning { hours: 4.0 },
+                ReminderRule::PastEndOfDay { time: "18:00".to_string() },
+                ReminderRule::NoTimerDuringWorkingHours {
+                    start: "09:00".to_string(),
+                    end: "17:00".to_string(),
+                    weekdays_only: true,
+                    grace_minutes: 30,
+                },
+            ],
+        }
+    }
+}
+
+// A reminder that is due, identified by `key` for repeat throttling
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reminder {
+    pub key: String,
+    pub title: String,
+    pub body: String,
+    pub actions: Vec<NotificationAction>,
+}
+
+// When each reminder key last fired
+static LAST_FIRED: Lazy<Mutex<HashMap<String, DateTime<Utc>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn parse_clock(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+// Evaluate the rules against the current timer; `last_active` is when a timer was last seen
+// running or paused. A paused timer counts as tracking, so "no timer" reminders stay quiet.
+pub fn due_reminders(
+    rules: &[ReminderRule],
+    timer: Option<&TimeEntry>,
+    paused: bool,
+    last_active: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Vec<Reminder> {
+    let local_now = now.with_timezone(&Local);
+    let today = local_now.date_naive();
+    let mut due = Vec::new();
+
+    for rule in rules {
+        match (rule, timer) {
+            (ReminderRule::LongRunning { hours }, Some(timer)) => {
+                let elapsed = entry_elapsed_seconds(timer, now);
+                if elapsed as f64 >= hours * 3600.0 {
+                    due.push(Reminder {
+                        key: format!("long_running:{}", timer.id),
+                        title: "Timer still running".to_string(),
+                        body: format!(
+                            "{} has been running for {}",
+                            project_label(timer.project.as_ref()),
+                            format_duration_minutes(elapsed as f64 / 60.0)
+                        ),
+                        actions: vec![NotificationAction::StopTimer, NotificationAction::Snooze],
+                    });
+                }
+            }
+            (ReminderRule::PastEndOfDay { time }, Some(timer))
+                if parse_clock(time).is_some_and(|end| local_now.time() >= end) =>
+            {
+                due.push(Reminder {
+                    key: format!("past_end_of_day:{}", today),
+                    title: "Working late?".to_string(),
+                    body: format!("{} is still running after {}", project_label(timer.project.as_ref()), time),
+                    actions: vec![NotificationAction::StopTimer, NotificationAction::Snooze],
+                });
+            }
+            (ReminderRule::NoTimerDuringWorkingHours { start, end, weekdays_only, grace_minutes }, None) if !paused => {
+                let (Some(start_time), Some(end_time)) = (parse_clock(start), parse_clock(end)) else {
+                    continue;
+                };
+                let is_weekend = matches!(local_now.weekday(), Weekday::Sat | Weekday::Sun);
+                if (*weekdays_only && is_weekend) || local_now.time() < start_time || local_now.time() >= end_time {
+                    continue;
+                }
+
+                // Only count inactivity from the start of today's working hours
+                let work_start = today
+                    .and_time(start_time)
+                    .and_local_timezone(Local)
+                    .single()
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or(now);
+                let inactive_since = last_active.max(work_start);
+                if now - inactive_since >= Duration::minutes(*grace_minutes as i64) {
+                    due.push(Reminder {
+                        key: format!("no_timer:{}", today),
+                        title: "No timer running".to_string(),
+                        body: format!("Nothing has been tracked for {} minutes", (now - inactive_since).num_minutes()),
+                        actions: vec![NotificationAction::StartTimer, NotificationAction::Snooze],
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    due
+}
+
+// Evaluate reminder rules periodically and fire native notifications
+pub async fn run_reminder_loop(app: AppHandle) {
+    let mut last_active = Utc::now();
+
+    loop {
+        tokio::time::sleep(tokio::time::Duration::from_secs(CHECK_INTERVAL_SECS)).await;
+
+        let now = Utc::now();
+        let timer = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+        let paused = PAUSED_TIMER.lock().unwrap().is_some();
+        if timer.is_some() || paused {
+            last_active = now;
+        }
+
+        let settings = preferences::get().reminders;
+        if !settings.enabled {
+            continue;
+        }
+
+        let repeat = Duration::minutes(settings.repeat_minutes as i64);
+        for reminder in due_reminders(&settings.rules, timer.as_ref(), paused, last_active, now) {
+            {
+                let mut last_fired = LAST_FIRED.lock().unwrap();
+                if last_fired.get(&reminder.key).is_some_and(|at| now - *at < repeat) {
+                    continue;
+                }
+                last_fired.insert(reminder.key.clone(), now);
+            }
+
+            notifications::notify(&app, NotificationCategory::Reminder, &reminder.title, &reminder.body, &reminder.actions);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn set_reminder_settings(settings: ReminderSettings) -> Result<ReminderSettings, String> {
+    let prefs = preferences::update(|prefs| prefs.reminders = settings)?;
+    Ok(prefs.reminders)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn working_hours() -> Vec<ReminderRule> {
+        vec![ReminderRule::NoTimerDuringWorkingHours {
+            start: "09:00".to_string(),
+            end: "17:00".to_string(),
+            weekdays_only: true,
+            grace_minutes: 30,
+        }]
+    }
+
+    // Monday 2024-03-04 at the given local time
+    fn monday(hour: u32, minute: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2024, 3, 4, hour, minute, 0).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn no_timer_fires_after_grace() {
+        let due = due_reminders(&working_hours(), None, false, monday(10, 0), monday(10, 45));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].key, "no_timer:2024-03-04");
+    }
+
+    #[test]
+    fn no_timer_waits_for_grace_from_work_start() {
+        let due = due_reminders(&working_hours(), None, false, monday(7, 0), monday(9, 20));
+        assert!(due.is_empty());
+    }
+
+    #[test]
+    fn no_timer_skipped_while_paused() {
+        let due = due_reminders(&working_hours(), None, true, monday(10, 0), monday(10, 45));
+        assert!(due.is_empty());
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::{Duration, Local, Utc};
+
+use crate::preferences;
+use crate::{fetch_time_entries_range, TimeEntryFilter};
+
+// The report math lives in the API client crate so the CLI shares it
+pub use freshtracks::reports::{build_report, parse_date, week_start, Report, ReportSettings, RoundingRule};
+
+// Totals for a date range; defaults to the current week and the configured rounding
+#[tauri::command]
+pub async fn get_report(from: Option<String>, to: Option<String>, rounding: Option<RoundingRule>) -> Result<Report, String> {
+    let today = Local::now().date_naive();
+    let from = match from {
+        Some(value) => parse_date(&value)?,
+        None => week_start(today),
+    };
+    let to = match to {
+        Some(value) => parse_date(&value)?,
+        None => from + Duration::days(6),
+    };
+    if to < from {
+        return Err("The end date is before the start date".to_string());
+    }
+
+    let rounding = rounding.unwrap_or(preferences::get().reports.rounding);
+    let entries = fetch_time_entries_range(&TimeEntryFilter {
+        from: Some(from.to_string()),
+        to: Some(to.to_string()),
+        ..Default::default()
+    }).await?;
+    Ok(build_report(&entries, from, to, rounding, Utc::now()))
+}
+
+// Totals for the single day `date` (defaults to today)
+#[tauri::command]
+pub async fn get_daily_summary(date: Option<String>) -> Result<Report, String> {
+    let day = date.unwrap_or_else(|| Local::now().date_naive().to_string());
+    get_report(Some(day.clone()), Some(day), None).await
+}
+
+// Totals for the week containing `date` (defaults to this week)
+#[tauri::command]
+pub async fn get_weekly_summary(date: Option<String>) -> Result<Report, String> {
+    let date = match date {
+        Some(value) => parse_date(&value)?,
+        None => Local::now().date_naive(),
+    };
+    let start = week_start(date);
+    get_report(Some(start.to_string()), Some((start + Duration::days(6)).to_string()), None).await
+}
+
+#[tauri::command]
+pub async fn set_report_settings(settings: ReportSettings) -> Result<ReportSettings, String> {
+    let prefs = preferences::update(|prefs| prefs.reports = settings)?;
+    Ok(prefs.reports)
+}
@@ -0,0 +1,429 @@
+use once_cell::sync::Lazy;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Listener, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::{broadcast, mpsc};
+
+use crate::control;
+use crate::preferences;
+use crate::search;
+use crate::suggestions;
+use crate::templates;
+use crate::undo;
+use crate::{
+    fetch_time_entries_range, get_active_timer, get_client_projects, get_clients,
+    get_recent_projects, get_time_entries, stop_timer_with_feedback, TimeEntryFilter,
+};
+
+// The socket lives in its own owner-only directory, so it is never reachable by
+// other users, not even between bind() and chmod()
+const SOCKET_DIR: &str = "rpc";
+const SOCKET_NAME: &str = "freshtracks.sock";
+
+// Longer request lines are rejected and the connection closed
+const MAX_LINE_BYTES: usize = 1024 * 1024;
+
+// App events forwarded to subscribers
+const FORWARDED_EVENTS: [&str; 8] = [
+    "timer-toggled",
+    "ws-timer-started",
+    "ws-timer-stopped",
+    "ws-timer-deleted",
+    "pomodoro-changed",
+    "idle-detected",
+    "away-detected",
+    "undo-available",
+];
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+// Backend failures (API errors, nothing to stop, ...)
+const SERVER_ERROR: i64 = -32000;
+
+static TIMER_EVENTS: Lazy<broadcast::Sender<(String, Value)>> = Lazy::new(|| broadcast::channel(64).0);
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+}
+
+// Backend errors are plain strings, like the Tauri commands
+impl From<String> for RpcError {
+    fn from(message: String) -> Self {
+        Self::new(SERVER_ERROR, message)
+    }
+}
+
+struct Subscription {
+    id: u64,
+    // None subscribes to every forwarded event
+    events: Option<Vec<String>>,
+}
+
+// Per-connection subscriptions
+#[derive(Default)]
+struct Subscriptions {
+    next_id: u64,
+    active: Vec<Subscription>,
+}
+
+#[derive(Deserialize)]
+struct StartParams {
+    project_id: i32,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StopParams {
+    // Defaults to whatever timer is running
+    #[serde(default)]
+    timer_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct RestartParams {
+    #[serde(default)]
+    entry_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+struct RangeParams {
+    #[serde(default)]
+    filter: TimeEntryFilter,
+}
+
+#[derive(Deserialize)]
+struct ClientParams {
+    client_id: i32,
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    #[serde(default)]
+    query: String,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Deserialize)]
+struct SuggestParams {
+    #[serde(default)]
+    project_id: Option<i32>,
+    #[serde(default)]
+    query: String,
+}
+
+#[derive(Deserialize)]
+struct TemplateParams {
+    template_id: u32,
+}
+
+#[derive(Deserialize)]
+struct SubscribeParams {
+    #[serde(default)]
+    events: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct UnsubscribeParams {
+    subscription: u64,
+}
+
+pub fn socket_path(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join(SOCKET_DIR).join(SOCKET_NAME))
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))
+}
+
+// Named params as an object; missing params count as empty
+fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+fn to_value<T: Serialize>(value: T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))
+}
+
+async fn call(app: &AppHandle, subscriptions: &Mutex<Subscriptions>, method: &str, params: Value) -> Result<Value, RpcError> {
+    match method {
+        "get_active_timer" => to_value(get_active_timer().await?),
+        "get_status" => to_value(control::status().await),
+        "start_timer" => {
+            let p: StartParams = parse_params(params)?;
+            to_value(control::start(app, p.project_id, p.description).await?)
+        }
+        "stop_timer" => {
+            let p: StopParams = parse_params(params)?;
+            match p.timer_id {
+                Some(timer_id) => to_value(stop_timer_with_feedback(app, timer_id).await?),
+                None => to_value(control::stop(app).await?),
+            }
+        }
+        "restart_timer" => {
+            let p: RestartParams = parse_params(params)?;
+            to_value(control::restart(app, p.entry_id).await?)
+        }
+        "get_time_entries" => to_value(get_time_entries().await?),
+        "get_time_entries_range" => {
+            let p: RangeParams = parse_params(params)?;
+            to_value(fetch_time_entries_range(&p.filter).await?)
+        }
+        "get_recent_projects" => to_value(get_recent_projects().await?),
+        "get_clients" => to_value(get_clients().await?),
+        "get_client_projects" => {
+            let p: ClientParams = parse_params(params)?;
+            to_value(get_client_projects(p.client_id).await?)
+        }
+        "search_projects" => {
+            let p: SearchParams = parse_params(params)?;
+            to_value(search::search(&p.query, p.limit.unwrap_or(10)).await)
+        }
+        "suggest_descriptions" => {
+            let p: SuggestParams = parse_params(params)?;
+            to_value(suggestions::suggest_descriptions(p.project_id, p.query).await?)
+        }
+        "get_templates" => to_value(preferences::get().templates),
+        "start_from_template" => {
+            let p: TemplateParams = parse_params(params)?;
+            to_value(templates::start(app, p.template_id).await?)
+        }
+        "undo_last_action" => to_value(undo::undo_last(app).await?),
+        "subscribe" => {
+            let p: SubscribeParams = parse_params(params)?;
+            let mut subs = subscriptions.lock().unwrap();
+            subs.next_id += 1;
+            let id = subs.next_id;
+            subs.active.push(Subscription { id, events: p.events });
+            Ok(json!({ "subscription": id, "events": FORWARDED_EVENTS }))
+        }
+        "unsubscribe" => {
+            let p: UnsubscribeParams = parse_params(params)?;
+            let mut subs = subscriptions.lock().unwrap();
+            let before = subs.active.len();
+            subs.active.retain(|s| s.id != p.subscription);
+            Ok(Value::Bool(subs.active.len() < before))
+        }
+        _ => Err(RpcError::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))),
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": error })
+}
+
+// Handle one request object; None for notifications (no "id")
+async fn handle_request(app: &AppHandle, subscriptions: &Mutex<Subscriptions>, request: Value) -> Option<Value> {
+    let Value::Object(request) = request else {
+        return Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, "Request must be an object")));
+    };
+    let id = request.get("id").cloned();
+    let response_id = id.clone().unwrap_or(Value::Null);
+
+    if request.get("jsonrpc").and_then(Value::as_str) != Some("2.0") {
+        return Some(error_response(response_id, RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\"")));
+    }
+    let Some(method) = request.get("method").and_then(Value::as_str) else {
+        return Some(error_response(response_id, RpcError::new(INVALID_REQUEST, "Missing method")));
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    let result = call(app, subscriptions, method, params).await;
+    // Notifications get no response, even on error
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => error_response(id, error),
+    })
+}
+
+// Handle one line: a request or a batch of requests
+async fn handle_line(app: &AppHandle, subscriptions: &Mutex<Subscriptions>, line: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => return Some(error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string()))),
+    };
+
+    match message {
+        Value::Array(batch) if batch.is_empty() => {
+            Some(error_response(Value::Null, RpcError::new(INVALID_REQUEST, "Empty batch")))
+        }
+        Value::Array(batch) => {
+            let mut responses = Vec::new();
+            for request in batch {
+                if let Some(response) = handle_request(app, subscriptions, request).await {
+                    responses.push(response);
+                }
+            }
+            // A batch of only notifications gets no reply at all
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        request => handle_request(app, subscriptions, request).await,
+    }
+}
+
+// Newline-delimited JSON in both directions
+async fn handle_connection(app: AppHandle, stream: UnixStream) {
+    let (reader, mut writer) = stream.into_split();
+    let (tx, mut rx) = mpsc::unbounded_channel::<Value>();
+    let subscriptions = Arc::new(Mutex::new(Subscriptions::default()));
+
+    let writer_task = tauri::async_runtime::spawn(async move {
+        while let Some(message) = rx.recv().await {
+            let mut line = message.to_string();
+            line.push('\n');
+            if writer.write_all(line.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let events_task = {
+        let tx = tx.clone();
+        let subscriptions = subscriptions.clone();
+        let mut events = TIMER_EVENTS.subscribe();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let (event, payload) = match events.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+                let targets: Vec<u64> = subscriptions
+                    .lock()
+                    .unwrap()
+                    .active
+                    .iter()
+                    .filter(|s| s.events.as_ref().map_or(true, |names| names.contains(&event)))
+                    .map(|s| s.id)
+                    .collect();
+                for subscription in targets {
+                    let notification = json!({
+                        "jsonrpc": "2.0",
+                        "method": "timer_event",
+                        "params": { "subscription": subscription, "event": event, "payload": payload },
+                    });
+                    if tx.send(notification).is_err() {
+                        return;
+                    }
+                }
+            }
+        })
+    };
+
+    let mut reader = BufReader::new(reader);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        // One byte over the limit tells an oversized line from one that fits exactly
+        let limit = MAX_LINE_BYTES as u64 + 1;
+        match (&mut reader).take(limit).read_until(b'\n', &mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        if buf.last() != Some(&b'\n') && buf.len() > MAX_LINE_BYTES {
+            let message = format!("Request is longer than {} bytes", MAX_LINE_BYTES);
+            let _ = tx.send(error_response(Value::Null, RpcError::new(INVALID_REQUEST, message)));
+            break;
+        }
+
+        let line = String::from_utf8_lossy(&buf);
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = handle_line(&app, &subscriptions, &line).await {
+            if tx.send(response).is_err() {
+                break;
+            }
+        }
+    }
+
+    events_task.abort();
+    drop(tx);
+    let _ = writer_task.await;
+}
+
+// Create the owner-only socket directory and clear out a stale socket. Fails when
+// another instance is already listening, rather than taking its socket over.
+async fn prepare_socket(path: &Path) -> Result<(), String> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to restrict {}: {}", dir.display(), e))?;
+    }
+
+    if !path.exists() {
+        return Ok(());
+    }
+    if UnixStream::connect(path).await.is_ok() {
+        return Err("another instance is already listening".to_string());
+    }
+    // Nobody answers: left behind by a run that didn't shut down cleanly
+    std::fs::remove_file(path).map_err(|e| format!("Failed to remove stale socket: {}", e))
+}
+
+// Listen on <app data dir>/rpc/freshtracks.sock (owner-only) for the app's lifetime
+pub fn start_server(app: &AppHandle) {
+    let path = match socket_path(app) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Failed to start JSON-RPC socket: {}", e);
+            return;
+        }
+    };
+
+    for name in FORWARDED_EVENTS {
+        app.listen_any(name, move |event| {
+            let payload = serde_json::from_str(event.payload()).unwrap_or(Value::Null);
+            let _ = TIMER_EVENTS.send((name.to_string(), payload));
+        });
+    }
+
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = prepare_socket(&path).await {
+            eprintln!("Not starting JSON-RPC socket {}: {}", path.display(), e);
+            return;
+        }
+
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to bind JSON-RPC socket {}: {}", path.display(), e);
+                return;
+            }
+        };
+        if let Err(e) = std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)) {
+            eprintln!("Failed to restrict JSON-RPC socket permissions: {}", e);
+        }
+        println!("JSON-RPC listening on {}", path.display());
+
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let app = app_handle.clone();
+                    tauri::async_runtime::spawn(handle_connection(app, stream));
+                }
+                Err(e) => eprintln!("JSON-RPC accept failed: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,76 @@
+use chrono::{DateTime, Duration, Utc};
+use freshtracks::search::{fetch_catalog, rank_projects, usage_scores, ProjectMatch};
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+
+use crate::{Project, RECENT_ENTRIES, RECENT_PROJECTS};
+
+// Refetch clients and their projects after this long
+const CATALOG_TTL_SECS: i64 = 300;
+const DEFAULT_LIMIT: usize = 10;
+
+struct ProjectCatalog {
+    fetched_at: Option<DateTime<Utc>>,
+    // Refreshes tried so far, successful or not
+    attempts: u64,
+    projects: Vec<Project>,
+}
+
+static PROJECT_CATALOG: Lazy<Mutex<ProjectCatalog>> = Lazy::new(|| {
+    Mutex::new(ProjectCatalog { fetched_at: None, attempts: 0, projects: Vec::new() })
+});
+
+// Held while the catalog is being fetched so concurrent searches share one refresh
+static CATALOG_REFRESH: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+// Cached projects (refreshed when stale) plus the recent projects from the tray
+pub async fn cached_projects() -> Vec<Project> {
+    let now = Utc::now();
+    let (stale, attempts) = {
+        let catalog = PROJECT_CATALOG.lock().unwrap();
+        let stale = catalog
+            .fetched_at
+            .map_or(true, |fetched| now - fetched > Duration::seconds(CATALOG_TTL_SECS));
+        (stale, catalog.attempts)
+    };
+
+    if stale {
+        let _refresh = CATALOG_REFRESH.lock().await;
+        // Whoever held the lock already refreshed (or failed to); use that result
+        let refreshed = PROJECT_CATALOG.lock().unwrap().attempts != attempts;
+        if !refreshed {
+            let result = fetch_catalog().await;
+            let mut catalog = PROJECT_CATALOG.lock().unwrap();
+            catalog.attempts += 1;
+            match result {
+                Ok(projects) => {
+                    catalog.projects = projects;
+                    catalog.fetched_at = Some(now);
+                }
+                Err(e) => eprintln!("Failed to refresh project catalog, using cache: {}", e),
+            }
+        }
+    }
+
+    let mut projects = PROJECT_CATALOG.lock().unwrap().projects.clone();
+    for project in RECENT_PROJECTS.lock().unwrap().iter() {
+        if !projects.iter().any(|p| p.id == project.id) {
+            projects.push(project.clone());
+        }
+    }
+    projects
+}
+
+pub async fn search(query: &str, limit: usize) -> Vec<ProjectMatch> {
+    let projects = cached_projects().await;
+    let recent_projects = RECENT_PROJECTS.lock().unwrap().clone();
+    let entries = RECENT_ENTRIES.lock().unwrap().clone();
+    let usage = usage_scores(&recent_projects, &entries, Utc::now());
+    rank_projects(&projects, &usage, query, limit)
+}
+
+// Fuzzy search over clients and projects ("acme web" → Acme Corp / Website Redesign)
+#[tauri::command]
+pub async fn search_projects(query: String, limit: Option<usize>) -> Result<Vec<ProjectMatch>, String> {
+    Ok(search(&query, limit.unwrap_or(DEFAULT_LIMIT)).await)
+}
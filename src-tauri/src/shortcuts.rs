@@ -0,0 +1,269 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+use crate::palette;
+use crate::preferences;
+use crate::templates;
+use crate::{
+    ACTIVE_TIMER_STATE, RECENT_ENTRIES, RECENT_PROJECTS,
+    last_stopped_entry, resume_paused_timer, show_main_window, start_timer_with_feedback,
+    stop_timer_with_feedback, toggle_pause, toggle_timer_via_shortcut,
+};
+
+// Actions that can be bound to a global shortcut
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    ToggleTimer,
+    ToggleWindow,
+    StartLastProject,
+    PauseTimer,
+    ResumeTimer,
+    AddNote,
+    NextRecentProject,
+    // Start the saved template with this id
+    StartTemplate(u32),
+    // Show or hide the quick-start palette window
+    OpenPalette,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ShortcutBinding {
+    pub action: ShortcutAction,
+    // Accelerator string, e.g. "Super+Shift+T" or "CmdOrCtrl+Alt+P"
+    pub accelerator: String,
+}
+
+// Registration result for a binding, so the UI can show conflicts
+#[derive(Debug, Serialize, Clone)]
+pub struct ShortcutStatus {
+    pub action: ShortcutAction,
+    pub accelerator: String,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+// Shortcuts currently registered with the OS, and what they trigger
+static REGISTERED: Lazy<Mutex<Vec<(Shortcut, ShortcutAction)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+// Last registration result for every configured binding
+static STATUSES: Lazy<Mutex<Vec<ShortcutStatus>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+pub fn default_bindings() -> Vec<ShortcutBinding> {
+    vec![
+        ShortcutBinding {
+            action: ShortcutAction::ToggleTimer,
+            accelerator: "Super+Shift+T".to_string(),
+        },
+        ShortcutBinding {
+            action: ShortcutAction::OpenPalette,
+            accelerator: "Super+Shift+Space".to_string(),
+        },
+    ]
+}
+
+// Whether two accelerator strings name the same key combination
+pub fn same_accelerator(a: &str, b: &str) -> bool {
+    match (a.parse::<Shortcut>(), b.parse::<Shortcut>()) {
+        (Ok(a), Ok(b)) => a.id() == b.id(),
+        _ => a.eq_ignore_ascii_case(b),
+    }
+}
+
+// (Re)register every binding from preferences, recording conflicts instead of failing
+pub fn register_all(app: &AppHandle) {
+    let global_shortcut = app.global_shortcut();
+
+    // Drop whatever we registered before
+    let previous: Vec<Shortcut> = REGISTERED.lock().unwrap().drain(..).map(|(s, _)| s).collect();
+    for shortcut in previous {
+        let _ = global_shortcut.unregister(shortcut);
+    }
+
+    let mut registered: Vec<(Shortcut, ShortcutAction)> = Vec::new();
+    let mut statuses = Vec::new();
+
+    for binding in preferences::get().shortcuts {
+        let result = match binding.accelerator.parse::<Shortcut>() {
+            Err(e) => Err(format!("Invalid shortcut: {}", e)),
+            Ok(shortcut) => {
+                if let Some((_, other)) = registered.iter().find(|(s, _)| s.id() == shortcut.id()) {
+                    Err(format!("Conflicts with {:?}", other))
+                } else {
+                    match global_shortcut.register(shortcut) {
+                        Ok(()) => {
+                            registered.push((shortcut, binding.action));
+                            Ok(())
+                        }
+                        Err(e) => Err(format!("Could not register (in use by another app?): {}", e)),
+                    }
+                }
+            }
+        };
+
+        match &result {
+            Ok(()) => println!("Global shortcut {} registered for {:?}", binding.accelerator, binding.action),
+            Err(e) => eprintln!("Failed to register global shortcut {}: {}", binding.accelerator, e),
+        }
+
+        statuses.push(ShortcutStatus {
+            action: binding.action,
+            accelerator: binding.accelerator,
+            registered: result.is_ok(),
+            error: result.err(),
+        });
+    }
+
+    *REGISTERED.lock().unwrap() = registered;
+    *STATUSES.lock().unwrap() = statuses;
+}
+
+// Dispatch a pressed shortcut to its action
+pub fn handle_shortcut(app: &AppHandle, shortcut: &Shortcut) {
+    let action = REGISTERED
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(s, _)| s.id() == shortcut.id())
+        .map(|(_, action)| *action);
+
+    if let Some(action) = action {
+        let app_clone = app.clone();
+        tauri::async_runtime::spawn(async move {
+            run_action(&app_clone, action).await;
+        });
+    }
+}
+
+async fn run_action(app: &AppHandle, action: ShortcutAction) {
+    match action {
+        ShortcutAction::ToggleTimer => toggle_timer_via_shortcut(app).await,
+        ShortcutAction::ToggleWindow => {
+            if let Some(window) = app.get_webview_window("main") {
+                if window.is_visible().unwrap_or(false) {
+                    let _ = window.hide();
+                } else {
+                    show_main_window(app);
+                }
+            }
+        }
+        ShortcutAction::StartLastProject => {
+            let last = last_stopped_entry(&RECENT_ENTRIES.lock().unwrap());
+            match last {
+                Some(entry) => switch_to_project(app, entry.project_id).await,
+                None => show_main_window(app),
+            }
+        }
+        // Pauses a running timer, or resumes a paused one
+        ShortcutAction::PauseTimer => {
+            if let Err(e) = toggle_pause(app).await {
+                eprintln!("Failed to pause/resume timer via shortcut: {}", e);
+            }
+        }
+        ShortcutAction::ResumeTimer => {
+            if let Err(e) = resume_paused_timer(app).await {
+                eprintln!("Failed to resume timer via shortcut: {}", e);
+            }
+        }
+        ShortcutAction::AddNote => {
+            let timer = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+            show_main_window(app);
+            if let Some(timer) = timer {
+                let _ = app.emit("add-note-requested", timer);
+            }
+        }
+        ShortcutAction::NextRecentProject => {
+            let projects = RECENT_PROJECTS.lock().unwrap().clone();
+            if projects.is_empty() {
+                show_main_window(app);
+                return;
+            }
+            let current = ACTIVE_TIMER_STATE.lock().unwrap().as_ref().map(|t| t.project_id);
+            let next_index = current
+                .and_then(|id| projects.iter().position(|p| p.id == id))
+                .map(|i| (i + 1) % projects.len())
+                .unwrap_or(0);
+            switch_to_project(app, projects[next_index].id).await;
+        }
+        ShortcutAction::StartTemplate(template_id) => {
+            if let Err(e) = templates::start(app, template_id).await {
+                eprintln!("Failed to start template via shortcut: {}", e);
+            }
+        }
+        ShortcutAction::OpenPalette => palette::toggle_palette(app),
+    }
+}
+
+// Stop the running timer (if any, and on a different project) and start the given project
+async fn switch_to_project(app: &AppHandle, project_id: i32) {
+    let timer = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+    if let Some(timer) = timer {
+        if timer.project_id == project_id {
+            return;
+        }
+        if let Err(e) = stop_timer_with_feedback(app, timer.id).await {
+            eprintln!("Failed to stop timer before switching project: {}", e);
+            return;
+        }
+    }
+
+    if let Err(e) = start_timer_with_feedback(app, project_id, None).await {
+        eprintln!("Failed to start timer via shortcut: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_shortcuts() -> Result<Vec<ShortcutStatus>, String> {
+    Ok(STATUSES.lock().unwrap().clone())
+}
+
+// Add a binding; rolled back if the OS refuses it or it clashes with an existing one
+#[tauri::command]
+pub async fn bind_shortcut(app: AppHandle, action: ShortcutAction, accelerator: String) -> Result<Vec<ShortcutStatus>, String> {
+    let shortcut = accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("Invalid shortcut: {}", e))?;
+
+    let existing = preferences::get()
+        .shortcuts
+        .into_iter()
+        .find(|b| b.accelerator.parse::<Shortcut>().map(|s| s.id() == shortcut.id()).unwrap_or(false));
+    if let Some(existing) = existing {
+        return Err(format!("{} is already bound to {:?}", accelerator, existing.action));
+    }
+
+    let binding = ShortcutBinding { action, accelerator: accelerator.clone() };
+    preferences::update(|prefs| prefs.shortcuts.push(binding.clone()))?;
+    register_all(&app);
+
+    let failure = STATUSES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|s| s.accelerator == accelerator && !s.registered)
+        .and_then(|s| s.error.clone());
+    if let Some(error) = failure {
+        preferences::update(|prefs| prefs.shortcuts.retain(|b| *b != binding))?;
+        register_all(&app);
+        return Err(error);
+    }
+
+    get_shortcuts().await
+}
+
+#[tauri::command]
+pub async fn unbind_shortcut(app: AppHandle, accelerator: String) -> Result<Vec<ShortcutStatus>, String> {
+    preferences::update(|prefs| prefs.shortcuts.retain(|b| !same_accelerator(&b.accelerator, &accelerator)))?;
+    register_all(&app);
+    get_shortcuts().await
+}
+
+#[tauri::command]
+pub async fn reset_shortcuts(app: AppHandle) -> Result<Vec<ShortcutStatus>, String> {
+    preferences::update(|prefs| prefs.shortcuts = default_bindings())?;
+    register_all(&app);
+    get_shortcuts().await
+}
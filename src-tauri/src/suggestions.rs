@@ -0,0 +1,248 @@
+use chrono::{DateTime, Duration, Local, Utc};
+use freshtracks::fuzzy;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::{fetch_time_entries_range, parse_timestamp, TimeEntry, TimeEntryFilter, RECENT_ENTRIES};
+
+// How far back the description index looks
+const INDEX_DAYS: i64 = 90;
+// Rebuild the index from the server after this long
+const INDEX_TTL_SECS: i64 = 600;
+// After a failed rebuild, serve the cache this long before trying again
+const INDEX_RETRY_SECS: i64 = 30;
+const MAX_SUGGESTIONS: usize = 10;
+
+#[derive(Debug, Serialize, Clone)]
+pub struct DescriptionSuggestion {
+    pub project_id: i32,
+    pub description: String,
+    // Number of entries that used this description
+    pub count: u32,
+    pub last_used: String,
+}
+
+struct DescriptionIndex {
+    built_at: Option<DateTime<Utc>>,
+    failed_at: Option<DateTime<Utc>>,
+    // Rebuilds tried so far, successful or not
+    attempts: u64,
+    entries: Vec<TimeEntry>,
+}
+
+static DESCRIPTION_INDEX: Lazy<Mutex<DescriptionIndex>> = Lazy::new(|| {
+    Mutex::new(DescriptionIndex { built_at: None, failed_at: None, attempts: 0, entries: Vec::new() })
+});
+
+// Held while the index is being rebuilt so concurrent lookups share one fetch
+static INDEX_REFRESH: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+// Entries for the index, refetching the history when the cache is stale
+async fn indexed_entries() -> Vec<TimeEntry> {
+    let now = Utc::now();
+    let (stale, attempts) = {
+        let index = DESCRIPTION_INDEX.lock().unwrap();
+        let expired = index
+            .built_at
+            .map_or(true, |built| now - built > Duration::seconds(INDEX_TTL_SECS));
+        let backing_off = index
+            .failed_at
+            .is_some_and(|failed| now - failed < Duration::seconds(INDEX_RETRY_SECS));
+        (expired && !backing_off, index.attempts)
+    };
+
+    if stale {
+        let _refresh = INDEX_REFRESH.lock().await;
+        // Whoever held the lock already rebuilt (or failed to); use that result
+        let refreshed = DESCRIPTION_INDEX.lock().unwrap().attempts != attempts;
+        if !refreshed {
+            let today = Local::now().date_naive();
+            let filter = TimeEntryFilter {
+                from: Some((today - Duration::days(INDEX_DAYS)).to_string()),
+                to: Some(today.to_string()),
+                ..Default::default()
+            };
+            let result = fetch_time_entries_range(&filter).await;
+            let mut index = DESCRIPTION_INDEX.lock().unwrap();
+            index.attempts += 1;
+            match result {
+                Ok(entries) => {
+                    index.entries = entries;
+                    index.built_at = Some(now);
+                    index.failed_at = None;
+                }
+                Err(e) => {
+                    index.failed_at = Some(Utc::now());
+                    eprintln!("Failed to refresh description index, using cache: {}", e);
+                }
+            }
+        }
+    }
+
+    // Recent entries pick up descriptions added since the last rebuild
+    let mut entries = DESCRIPTION_INDEX.lock().unwrap().entries.clone();
+    for entry in RECENT_ENTRIES.lock().unwrap().iter() {
+        if !entries.iter().any(|e| e.id == entry.id) {
+            entries.push(entry.clone());
+        }
+    }
+    entries
+}
+
+// Distinct descriptions matching `query`, ranked by match quality, how often
+// they were used and how recently
+pub fn rank_descriptions(entries: &[TimeEntry], project_id: Option<i32>, query: &str, now: DateTime<Utc>) -> Vec<DescriptionSuggestion> {
+    // (project, lowercased description) → (description, count, last used)
+    let mut stats: HashMap<(i32, String), (String, u32, DateTime<Utc>)> = HashMap::new();
+    for entry in entries {
+        if project_id.is_some_and(|id| id != entry.project_id) {
+            continue;
+        }
+        let Some(description) = entry.description.as_deref().map(str::trim).filter(|d| !d.is_empty()) else {
+            continue;
+        };
+        let Some(started_at) = parse_timestamp(&entry.started_at) else {
+            continue;
+        };
+
+        let stat = stats
+            .entry((entry.project_id, description.to_lowercase()))
+            .or_insert_with(|| (description.to_string(), 0, started_at));
+        stat.1 += 1;
+        // Keep the most recent spelling
+        if started_at >= stat.2 {
+            stat.0 = description.to_string();
+            stat.2 = started_at;
+        }
+    }
+
+    let mut ranked: Vec<(f64, DateTime<Utc>, DescriptionSuggestion)> = stats
+        .into_iter()
+        .filter_map(|((project_id, _), (description, count, last_used))| {
+            let quality = fuzzy::score(query, &description)?;
+            let days_ago = (now - last_used).num_hours().max(0) as f64 / 24.0;
+            let recency = 1.0 / (1.0 + days_ago / 7.0);
+            let frequency = (1.0 + count as f64).ln();
+            let score = quality * 2.0 + recency + frequency * 0.5;
+            Some((score, last_used, DescriptionSuggestion {
+                project_id,
+                description,
+                count,
+                last_used: last_used.to_rfc3339(),
+            }))
+        })
+        .collect();
+
+    // Equal scores fall back to the most recently used, then alphabetical, so the order is stable
+    ranked.sort_by(|a, b| {
+        b.0.total_cmp(&a.0)
+            .then_with(|| b.1.cmp(&a.1))
+            .then_with(|| a.2.description.cmp(&b.2.description))
+    });
+    ranked.into_iter().take(MAX_SUGGESTIONS).map(|(_, _, s)| s).collect()
+}
+
+// Past descriptions for autocompletion; all projects when project_id is None
+#[tauri::command]
+pub async fn suggest_descriptions(project_id: Option<i32>, query: String) -> Result<Vec<DescriptionSuggestion>, String> {
+    let entries = indexed_entries().await;
+    Ok(rank_descriptions(&entries, project_id, &query, Utc::now()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_timestamp;
+
+    fn now() -> DateTime<Utc> {
+        parse_timestamp("2024-06-15T12:00:00Z").unwrap()
+    }
+
+    fn entry(id: i32, project_id: i32, description: &str, days_ago: i64) -> TimeEntry {
+        TimeEntry {
+            id,
+            project_id,
+            project: None,
+            description: Some(description.to_string()),
+            started_at: format_timestamp(now() - Duration::days(days_ago)),
+            stopped_at: None,
+            resumed_at: None,
+            duration_minutes: None,
+            is_billable: true,
+        }
+    }
+
+    fn descriptions(suggestions: &[DescriptionSuggestion]) -> Vec<&str> {
+        suggestions.iter().map(|s| s.description.as_str()).collect()
+    }
+
+    #[test]
+    fn merges_case_variants_and_keeps_latest_spelling() {
+        let entries = vec![
+            entry(1, 1, "code review", 3),
+            entry(2, 1, "Code Review ", 1),
+            entry(3, 1, "", 0),
+        ];
+        let ranked = rank_descriptions(&entries, None, "", now());
+
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].description, "Code Review");
+        assert_eq!(ranked[0].count, 2);
+        assert_eq!(ranked[0].last_used, (now() - Duration::days(1)).to_rfc3339());
+    }
+
+    #[test]
+    fn same_description_is_distinct_per_project() {
+        let entries = vec![entry(1, 1, "Standup", 1), entry(2, 2, "Standup", 1)];
+
+        assert_eq!(rank_descriptions(&entries, None, "", now()).len(), 2);
+        let for_project = rank_descriptions(&entries, Some(2), "", now());
+        assert_eq!(for_project.len(), 1);
+        assert_eq!(for_project[0].project_id, 2);
+    }
+
+    #[test]
+    fn frequent_recent_descriptions_rank_first() {
+        let mut entries = vec![entry(1, 1, "Old task", 60)];
+        entries.extend((2..6).map(|id| entry(id, 1, "Daily task", 1)));
+        entries.push(entry(6, 1, "Fresh task", 0));
+
+        let ranked = rank_descriptions(&entries, None, "task", now());
+        assert_eq!(descriptions(&ranked), vec!["Daily task", "Fresh task", "Old task"]);
+    }
+
+    #[test]
+    fn prefix_matches_rank_above_substring_matches() {
+        let entries = vec![
+            entry(1, 1, "Preview deploy", 1),
+            entry(2, 1, "Preview deploy", 1),
+            entry(3, 1, "Review PR", 1),
+            entry(4, 1, "Billing", 0),
+        ];
+
+        let ranked = rank_descriptions(&entries, None, "review", now());
+        assert_eq!(descriptions(&ranked), vec!["Review PR", "Preview deploy"]);
+    }
+
+    #[test]
+    fn equal_scores_break_ties_by_recency_then_text() {
+        // Minutes apart within the same hour, so recency scores the same
+        let at = |id, description: &str, minutes| {
+            let mut e = entry(id, 1, description, 1);
+            e.started_at = format_timestamp(now() - Duration::days(1) - Duration::minutes(minutes));
+            e
+        };
+        let entries = vec![at(1, "Gamma", 10), at(2, "Beta", 5), at(3, "Alpha", 10)];
+
+        let ranked = rank_descriptions(&entries, None, "", now());
+        assert_eq!(descriptions(&ranked), vec!["Beta", "Alpha", "Gamma"]);
+    }
+
+    #[test]
+    fn limits_results() {
+        let entries: Vec<TimeEntry> = (0..15).map(|i| entry(i, 1, &format!("Task {}", i), i as i64)).collect();
+        assert_eq!(rank_descriptions(&entries, None, "", now()).len(), MAX_SUGGESTIONS);
+    }
+}
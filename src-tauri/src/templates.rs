@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+
+use crate::notifications::{self, NotificationAction, NotificationCategory};
+use crate::preferences;
+use crate::shortcuts::{self, ShortcutAction};
+use crate::{
+    announce_timer_started, rebuild_tray_menu, start_timer_internal, stop_timer_with_feedback,
+    update_entry_internal, TimeEntry, UpdateEntryData, ACTIVE_TIMER_STATE,
+};
+
+// A saved way of starting a timer
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TimerTemplate {
+    // Assigned on first save (0 means new)
+    #[serde(default)]
+    pub id: u32,
+    pub name: String,
+    pub project_id: i32,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default = "default_billable")]
+    pub is_billable: bool,
+    // Appended to the description as #hashtags, since entries have no tag field
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_billable() -> bool {
+    true
+}
+
+impl TimerTemplate {
+    pub fn entry_description(&self) -> Option<String> {
+        let mut parts: Vec<String> = self
+            .description
+            .iter()
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty())
+            .collect();
+        for tag in &self.tags {
+            let tag = tag.trim().trim_start_matches('#');
+            if !tag.is_empty() {
+                parts.push(format!("#{}", tag));
+            }
+        }
+        Some(parts.join(" ")).filter(|d| !d.is_empty())
+    }
+}
+
+pub fn find(template_id: u32) -> Option<TimerTemplate> {
+    preferences::get().templates.into_iter().find(|t| t.id == template_id)
+}
+
+// Stop whatever is running and start a timer from the template
+pub async fn start(app: &AppHandle, template_id: u32) -> Result<TimeEntry, String> {
+    let template = find(template_id).ok_or_else(|| format!("Template {} was not found", template_id))?;
+
+    let running = ACTIVE_TIMER_STATE.lock().unwrap().clone();
+    if let Some(running) = running {
+        stop_timer_with_feedback(app, running.id).await?;
+    }
+
+    let description = template.entry_description();
+    let started = start_timer_internal(template.project_id, description.clone()).await?;
+    announce_timer_started(app, &started, "Timer Started").await;
+
+    // The start endpoint has no billable flag, so apply it afterwards when it differs.
+    // The timer is already running either way, so a failure here is only reported.
+    if started.is_billable == template.is_billable {
+        return Ok(started);
+    }
+    let updated = update_entry_internal(started.id, UpdateEntryData {
+        project_id: started.project_id,
+        description,
+        started_at: started.started_at.clone(),
+        stopped_at: None,
+        is_billable: template.is_billable,
+        resumed_at: None,
+    }).await;
+
+    match updated {
+        Ok(updated) => {
+            *ACTIVE_TIMER_STATE.lock().unwrap() = Some(updated.clone());
+            let _ = app.emit("timer-toggled", ());
+            Ok(updated)
+        }
+        Err(e) => {
+            eprintln!("Failed to apply billable flag from template {}: {}", template.id, e);
+            let billable = if template.is_billable { "billable" } else { "non-billable" };
+            notifications::notify(
+                app,
+                NotificationCategory::Timer,
+                "Timer started, billable flag not set",
+                &format!("Couldn't mark the entry {}: {}", billable, e),
+                &[NotificationAction::Open],
+            );
+            Ok(started)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_templates() -> Result<Vec<TimerTemplate>, String> {
+    Ok(preferences::get().templates)
+}
+
+// Add a template (id 0) or replace the one with the same id
+#[tauri::command]
+pub async fn save_template(app: AppHandle, template: TimerTemplate) -> Result<Vec<TimerTemplate>, String> {
+    if template.name.trim().is_empty() {
+        return Err("Template name can't be empty".to_string());
+    }
+
+    let prefs = preferences::update(|prefs| {
+        let mut template = template;
+        match prefs.templates.iter_mut().find(|t| template.id != 0 && t.id == template.id) {
+            Some(existing) => *existing = template,
+            None => {
+                template.id = prefs.templates.iter().map(|t| t.id).max().unwrap_or(0) + 1;
+                prefs.templates.push(template);
+            }
+        }
+    })?;
+
+    rebuild_tray_menu(&app);
+    Ok(prefs.templates)
+}
+
+// Remove a template along with any shortcut bound to it
+#[tauri::command]
+pub async fn delete_template(app: AppHandle, template_id: u32) -> Result<Vec<TimerTemplate>, String> {
+    let prefs = preferences::update(|prefs| {
+        prefs.templates.retain(|t| t.id != template_id);
+        prefs.shortcuts.retain(|b| b.action != ShortcutAction::StartTemplate(template_id));
+    })?;
+
+    shortcuts::register_all(&app);
+    rebuild_tray_menu(&app);
+    Ok(prefs.templates)
+}
+
+#[tauri::command]
+pub async fn start_from_template(app: AppHandle, template_id: u32) -> Result<TimeEntry, String> {
+    start(&app, template_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template(description: Option<&str>, tags: &[&str]) -> TimerTemplate {
+        TimerTemplate {
+            id: 1,
+            name: "Template".to_string(),
+            project_id: 1,
+            description: description.map(str::to_string),
+            is_billable: true,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn tags_are_appended_as_hashtags() {
+        let t = template(Some(" Code review "), &["client", " #urgent ", "##ops"]);
+        assert_eq!(t.entry_description().as_deref(), Some("Code review #client #urgent #ops"));
+    }
+
+    #[test]
+    fn blank_tags_are_skipped() {
+        let t = template(Some("Standup"), &["", " # ", "  "]);
+        assert_eq!(t.entry_description().as_deref(), Some("Standup"));
+    }
+
+    #[test]
+    fn tags_alone_make_the_description() {
+        assert_eq!(template(None, &["support"]).entry_description().as_deref(), Some("#support"));
+        assert_eq!(template(Some("   "), &["support"]).entry_description().as_deref(), Some("#support"));
+    }
+
+    #[test]
+    fn empty_or_missing_description_gives_none() {
+        assert_eq!(template(None, &[]).entry_description(), None);
+        assert_eq!(template(Some(""), &[]).entry_description(), None);
+        assert_eq!(template(Some("  "), &[" "]).entry_description(), None);
+    }
+
+    #[test]
+    fn saved_template_without_optional_fields() {
+        let t: TimerTemplate = serde_json::from_str(r#"{"name": "Admin", "project_id": 3}"#).unwrap();
+        assert_eq!(t.id, 0);
+        assert!(t.is_billable);
+        assert_eq!(t.entry_description(), None);
+    }
+}
@@ -0,0 +1,206 @@
+use chrono::{Duration, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter};
+
+use crate::entries::{create_past_entry, find_entries, restore_data, start_timer_at};
+use crate::{
+    delete_entry_internal, format_timestamp, parse_timestamp, project_label, refresh_tray_menu_data,
+    restart_timer, update_entry_internal, TimeEntry, ACTIVE_TIMER_STATE, RECENT_ENTRIES,
+};
+
+// How long an action can still be undone
+const UNDO_WINDOW_SECS: i64 = 60;
+
+// Older actions are dropped beyond this many
+const MAX_UNDO_ACTIONS: usize = 20;
+
+// A destructive action and the entry state from before it
+#[derive(Debug, Serialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UndoAction {
+    Deleted { entry: TimeEntry },
+    Updated { entries: Vec<TimeEntry> },
+    Stopped { entry: TimeEntry },
+    // An entry split in two: the original as it was and the second half created from it
+    Split { original: TimeEntry, created: TimeEntry },
+    // Entries merged into `survivor`, as they were before the merge
+    Merged { survivor: TimeEntry, removed: Vec<TimeEntry> },
+}
+
+impl UndoAction {
+    fn label(&self) -> String {
+        match self {
+            UndoAction::Deleted { entry } => format!("Delete {}", project_label(entry.project.as_ref())),
+            UndoAction::Updated { entries } if entries.len() == 1 => {
+                format!("Edit {}", project_label(entries[0].project.as_ref()))
+            }
+            UndoAction::Updated { entries } => format!("Edit {} entries", entries.len()),
+            UndoAction::Stopped { entry } => format!("Stop {}", project_label(entry.project.as_ref())),
+            UndoAction::Split { original, .. } => format!("Split {}", project_label(original.project.as_ref())),
+            UndoAction::Merged { removed, .. } => format!("Merge {} entries", removed.len() + 1),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct UndoRecord {
+    // Identifies this record for undo buttons shown alongside it (e.g. in notifications)
+    pub id: u64,
+    pub action: UndoAction,
+    pub label: String,
+    pub recorded_at: String,
+}
+
+static UNDO_STACK: Lazy<Mutex<Vec<UndoRecord>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static NEXT_UNDO_ID: Lazy<Mutex<u64>> = Lazy::new(|| Mutex::new(1));
+
+// State of an entry before changing it, from the cached timer and recent entries; only
+// entries the cache doesn't have are looked up on the server
+pub async fn snapshot(entry_id: i32) -> Option<TimeEntry> {
+    let active = ACTIVE_TIMER_STATE.lock().unwrap().clone().filter(|t| t.id == entry_id);
+    let cached = active.or_else(|| RECENT_ENTRIES.lock().unwrap().iter().find(|e| e.id == entry_id).cloned());
+    if cached.is_some() {
+        return cached;
+    }
+
+    match find_entries(&[entry_id]).await {
+        Ok(mut entries) => entries.pop(),
+        Err(e) => {
+            eprintln!("Could not snapshot entry {} for undo: {}", entry_id, e);
+            None
+        }
+    }
+}
+
+// Push an undoable action and return its record id
+pub fn record(app: &AppHandle, action: UndoAction) -> u64 {
+    let id = {
+        let mut next = NEXT_UNDO_ID.lock().unwrap();
+        *next += 1;
+        *next - 1
+    };
+    let record = UndoRecord {
+        id,
+        label: action.label(),
+        action,
+        recorded_at: format_timestamp(Utc::now()),
+    };
+
+    {
+        let mut stack = UNDO_STACK.lock().unwrap();
+        stack.push(record.clone());
+        if stack.len() > MAX_UNDO_ACTIONS {
+            stack.remove(0);
+        }
+    }
+    let _ = app.emit("undo-available", record);
+    id
+}
+
+// Drop actions that are past the undo window
+fn prune(stack: &mut Vec<UndoRecord>) {
+    let cutoff = Utc::now() - Duration::seconds(UNDO_WINDOW_SECS);
+    stack.retain(|r| parse_timestamp(&r.recorded_at).is_some_and(|at| at > cutoff));
+}
+
+// Bring a deleted entry back; it gets a new id
+async fn recreate(entry: &TimeEntry) -> Result<(), String> {
+    match entry.stopped_at.clone() {
+        Some(stopped_at) => {
+            create_past_entry(entry.project_id, entry.description.clone(), entry.started_at.clone(), stopped_at, entry.is_billable).await?;
+        }
+        None => {
+            start_timer_at(entry, entry.started_at.clone()).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn revert(action: &UndoAction) -> Result<(), String> {
+    match action {
+        UndoAction::Deleted { entry } => recreate(entry).await?,
+        UndoAction::Updated { entries } => {
+            for entry in entries {
+                update_entry_internal(entry.id, restore_data(entry)).await?;
+            }
+        }
+        UndoAction::Stopped { entry } if entry.resumed_at.is_none() => {
+            // Restarting would only count from now, so replace the entry with a running
+            // copy from the original start; the time since the stop counts as well
+            let restored = start_timer_at(entry, entry.started_at.clone()).await?;
+            if let Err(e) = delete_entry_internal(entry.id).await {
+                let _ = delete_entry_internal(restored.id).await;
+                return Err(e);
+            }
+        }
+        UndoAction::Stopped { entry } => {
+            // Earlier pauses live in the banked duration, which only a restart keeps
+            restart_timer(entry.id).await?;
+        }
+        UndoAction::Split { original, created } => {
+            // Remove the second half first so the restored original doesn't overlap it
+            delete_entry_internal(created.id).await?;
+            update_entry_internal(original.id, restore_data(original)).await?;
+        }
+        UndoAction::Merged { survivor, removed } => {
+            update_entry_internal(survivor.id, restore_data(survivor)).await?;
+            for entry in removed {
+                recreate(entry).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Revert the most recent action still inside the undo window
+pub async fn undo_last(app: &AppHandle) -> Result<UndoRecord, String> {
+    let record = {
+        let mut stack = UNDO_STACK.lock().unwrap();
+        prune(&mut stack);
+        stack.pop()
+    }
+    .ok_or_else(|| "Nothing to undo".to_string())?;
+    undo_record(app, record).await
+}
+
+// Revert one specific action (e.g. the stop a notification was shown for), even if
+// newer actions have been recorded since
+pub async fn undo_by_id(app: &AppHandle, id: u64) -> Result<UndoRecord, String> {
+    let record = {
+        let mut stack = UNDO_STACK.lock().unwrap();
+        prune(&mut stack);
+        stack.iter().position(|r| r.id == id).map(|i| stack.remove(i))
+    }
+    .ok_or_else(|| "That action can no longer be undone".to_string())?;
+    undo_record(app, record).await
+}
+
+async fn undo_record(app: &AppHandle, record: UndoRecord) -> Result<UndoRecord, String> {
+    if let Err(e) = revert(&record.action).await {
+        // Keep it available (in its original place) so the user can retry
+        let mut stack = UNDO_STACK.lock().unwrap();
+        let index = stack.partition_point(|r| r.id < record.id);
+        stack.insert(index, record);
+        return Err(format!("Undo failed: {}", e));
+    }
+
+    println!("Undid: {}", record.label);
+    let _ = app.emit("timer-toggled", ());
+    refresh_tray_menu_data(app).await;
+    Ok(record)
+}
+
+#[tauri::command]
+pub async fn undo_last_action(app: AppHandle) -> Result<UndoRecord, String> {
+    undo_last(&app).await
+}
+
+// Actions that can still be undone, most recent last
+#[tauri::command]
+pub async fn get_undo_stack() -> Result<Vec<UndoRecord>, String> {
+    let mut stack = UNDO_STACK.lock().unwrap();
+    prune(&mut stack);
+    Ok(stack.clone())
+}
@@ -0,0 +1,455 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::entries::{entries_around, entry_interval};
+use crate::preferences;
+use crate::reports::parse_date;
+use crate::{fetch_time_entries_range, parse_timestamp, project_label, TimeEntry, TimeEntryFilter, UpdateEntryData};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ValidationSettings {
+    // Working hours ("HH:MM", local) used for gap detection
+    pub working_start: String,
+    pub working_end: String,
+    // Gaps between entries longer than this are reported
+    pub max_gap_minutes: u32,
+}
+
+impl Default for ValidationSettings {
+    fn default() -> Self {
+        Self {
+            working_start: "09:00".to_string(),
+            working_end: "17:00".to_string(),
+            max_gap_minutes: 60,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    Overlap,
+    NegativeDuration,
+    SpansMidnight,
+    LargeGap,
+    FutureTime,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct ValidationWarning {
+    pub kind: WarningKind,
+    // Entries involved (two for overlaps, the neighbours for gaps)
+    pub entry_ids: Vec<i32>,
+    // Interval the warning refers to (the overlap, the gap, or the entry)
+    pub from: String,
+    pub to: String,
+    pub message: String,
+}
+
+fn local_time(dt: DateTime<Utc>) -> String {
+    dt.with_timezone(&Local).format("%H:%M").to_string()
+}
+
+fn local_instant(date: NaiveDate, time: NaiveTime) -> Option<DateTime<Utc>> {
+    date.and_time(time)
+        .and_local_timezone(Local)
+        .earliest()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn warning(kind: WarningKind, entry_ids: Vec<i32>, from: DateTime<Utc>, to: DateTime<Utc>, message: String) -> ValidationWarning {
+    ValidationWarning {
+        kind,
+        entry_ids,
+        from: from.to_rfc3339(),
+        to: to.to_rfc3339(),
+        message,
+    }
+}
+
+// Scan the entries that touch the local day `date` for problems
+pub fn validate_day(entries: &[TimeEntry], date: NaiveDate, settings: &ValidationSettings, now: DateTime<Utc>) -> Vec<ValidationWarning> {
+    let mut warnings = Vec::new();
+    let (Some(day_start), Some(day_end)) = (
+        local_instant(date, NaiveTime::MIN),
+        local_instant(date + Duration::days(1), NaiveTime::MIN),
+    ) else {
+        return warnings;
+    };
+
+    // Intervals touching this day, sorted by start
+    let mut intervals: Vec<(&TimeEntry, DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+    for entry in entries {
+        let Some(start) = parse_timestamp(&entry.started_at) else {
+            continue;
+        };
+        let stopped = entry.stopped_at.as_deref().and_then(parse_timestamp);
+
+        if stopped.is_some_and(|end| end < start) || entry.duration_minutes.is_some_and(|m| m < 0.0) {
+            if start >= day_start && start < day_end {
+                let end = stopped.unwrap_or(start);
+                warnings.push(warning(
+                    WarningKind::NegativeDuration,
+                    vec![entry.id],
+                    end.min(start),
+                    end.max(start),
+                    format!("{} stops before it starts", project_label(entry.project.as_ref())),
+                ));
+            }
+            continue;
+        }
+
+        let Some((start, end)) = entry_interval(entry, now) else {
+            continue;
+        };
+        if end <= day_start || start >= day_end {
+            continue;
+        }
+
+        if start < day_start || end > day_end {
+            warnings.push(warning(
+                WarningKind::SpansMidnight,
+                vec![entry.id],
+                start,
+                end,
+                format!("{} runs past midnight", project_label(entry.project.as_ref())),
+            ));
+        }
+        if end > now + Duration::minutes(1) {
+            warnings.push(warning(
+                WarningKind::FutureTime,
+                vec![entry.id],
+                now,
+                end,
+                format!("{} ends in the future", project_label(entry.project.as_ref())),
+            ));
+        }
+        intervals.push((entry, start, end));
+    }
+    intervals.sort_by_key(|(_, start, _)| *start);
+
+    // Overlaps between any two entries
+    for (i, (a, a_start, a_end)) in intervals.iter().enumerate() {
+        for (b, b_start, b_end) in intervals.iter().skip(i + 1) {
+            if *b_start >= *a_end {
+                break;
+            }
+            let from = (*a_start).max(*b_start);
+            let to = (*a_end).min(*b_end);
+            warnings.push(warning(
+                WarningKind::Overlap,
+                vec![a.id, b.id],
+                from,
+                to,
+                format!(
+                    "{} and {} overlap from {} to {}",
+                    project_label(a.project.as_ref()),
+                    project_label(b.project.as_ref()),
+                    local_time(from),
+                    local_time(to)
+                ),
+            ));
+        }
+    }
+
+    // Gaps within working hours, between the first and last entry of the day
+    let working = (
+        NaiveTime::parse_from_str(&settings.working_start, "%H:%M").ok().and_then(|t| local_instant(date, t)),
+        NaiveTime::parse_from_str(&settings.working_end, "%H:%M").ok().and_then(|t| local_instant(date, t)),
+    );
+    if let (Some(work_start), Some(work_end)) = working {
+        let max_gap = Duration::minutes(settings.max_gap_minutes as i64);
+        let mut covered_until: Option<(DateTime<Utc>, i32)> = None;
+
+        for (entry, start, end) in &intervals {
+            if let Some((previous_end, previous_id)) = covered_until {
+                let gap_start = previous_end.max(work_start);
+                let gap_end = (*start).min(work_end);
+                if gap_end - gap_start > max_gap {
+                    warnings.push(warning(
+                        WarningKind::LargeGap,
+                        vec![previous_id, entry.id],
+                        gap_start,
+                        gap_end,
+                        format!(
+                            "Nothing tracked from {} to {}",
+                            local_time(gap_start),
+                            local_time(gap_end)
+                        ),
+                    ));
+                }
+            }
+            if covered_until.map_or(true, |(until, _)| until < *end) {
+                covered_until = Some((*end, entry.id));
+            }
+        }
+    }
+
+    warnings
+}
+
+// Warnings that a proposed update to `entry_id` would cause (or keep) on its day, given the
+// entries around it
+pub fn validate_update(
+    mut entries: Vec<TimeEntry>,
+    entry_id: i32,
+    data: &UpdateEntryData,
+    settings: &ValidationSettings,
+    now: DateTime<Utc>,
+) -> Result<Vec<ValidationWarning>, String> {
+    let start = parse_timestamp(&data.started_at).ok_or_else(|| format!("Invalid started_at: {}", data.started_at))?;
+    let existing = entries.iter().position(|e| e.id == entry_id);
+    let original = existing.map(|i| entries.remove(i));
+
+    // Duration is recomputed by the server, so drop any stale value for the proposal
+    entries.push(TimeEntry {
+        id: entry_id,
+        project_id: data.project_id,
+        project: original.as_ref().and_then(|e| e.project.clone()).filter(|p| p.id == data.project_id),
+        description: data.description.clone(),
+        started_at: data.started_at.clone(),
+        stopped_at: data.stopped_at.clone(),
+        resumed_at: None,
+        duration_minutes: None,
+        is_billable: data.is_billable,
+    });
+
+    let date = start.with_timezone(&Local).date_naive();
+    let warnings = validate_day(&entries, date, settings, now)
+        .into_iter()
+        .filter(|w| w.entry_ids.contains(&entry_id))
+        .collect();
+    Ok(warnings)
+}
+
+// validate_update against the entries the server has around the proposed times
+pub async fn check_update(entry_id: i32, data: &UpdateEntryData) -> Result<Vec<ValidationWarning>, String> {
+    let start = parse_timestamp(&data.started_at).ok_or_else(|| format!("Invalid started_at: {}", data.started_at))?;
+    let end = match data.stopped_at.as_deref() {
+        Some(stopped) => parse_timestamp(stopped).ok_or_else(|| format!("Invalid stopped_at: {}", stopped))?,
+        None => Utc::now(),
+    };
+
+    let entries = entries_around(start.min(end), start.max(end)).await?;
+    validate_update(entries, entry_id, data, &preferences::get().validation, Utc::now())
+}
+
+// Edits that can't be saved: an entry stopping before it starts, or ending in the future.
+// Overlaps, gaps and midnight crossings stay warnings.
+pub fn reject_invalid(warnings: &[ValidationWarning]) -> Result<(), String> {
+    match warnings
+        .iter()
+        .find(|w| matches!(w.kind, WarningKind::NegativeDuration | WarningKind::FutureTime))
+    {
+        Some(w) => Err(w.message.clone()),
+        None => Ok(()),
+    }
+}
+
+// Warnings for the entries of a local day (defaults to today)
+#[tauri::command]
+pub async fn validate_entries(date: Option<String>) -> Result<Vec<ValidationWarning>, String> {
+    let date = match date {
+        Some(value) => parse_date(&value)?,
+        None => Local::now().date_naive(),
+    };
+    // Include the day before so entries running past midnight are seen
+    let entries = fetch_time_entries_range(&TimeEntryFilter {
+        from: Some((date - Duration::days(1)).to_string()),
+        to: Some(date.to_string()),
+        ..Default::default()
+    }).await?;
+
+    Ok(validate_day(&entries, date, &preferences::get().validation, Utc::now()))
+}
+
+// Check an edit before submitting it with update_entry
+#[tauri::command]
+pub async fn check_entry_update(entry_id: i32, data: UpdateEntryData) -> Result<Vec<ValidationWarning>, String> {
+    check_update(entry_id, &data).await
+}
+
+#[tauri::command]
+pub async fn set_validation_settings(settings: ValidationSettings) -> Result<ValidationSettings, String> {
+    let prefs = preferences::update(|prefs| prefs.validation = settings)?;
+    Ok(prefs.validation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format_timestamp;
+
+    fn day() -> NaiveDate {
+        parse_date("2024-06-05").unwrap()
+    }
+
+    // Local time on `day()`, offset by whole days
+    fn local(days: i64, hour: u32, minute: u32) -> DateTime<Utc> {
+        let time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        local_instant(day() + Duration::days(days), time).unwrap()
+    }
+
+    fn entry(id: i32, start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> TimeEntry {
+        TimeEntry {
+            id,
+            project_id: 1,
+            project: None,
+            description: None,
+            started_at: format_timestamp(start),
+            stopped_at: end.map(format_timestamp),
+            resumed_at: None,
+            duration_minutes: end.map(|end| (end - start).num_minutes() as f64),
+            is_billable: true,
+        }
+    }
+
+    fn kinds(warnings: &[ValidationWarning]) -> Vec<(WarningKind, Vec<i32>)> {
+        warnings.iter().map(|w| (w.kind, w.entry_ids.clone())).collect()
+    }
+
+    fn validate(entries: &[TimeEntry]) -> Vec<ValidationWarning> {
+        validate_day(entries, day(), &ValidationSettings::default(), local(1, 12, 0))
+    }
+
+    #[test]
+    fn clean_day_has_no_warnings() {
+        let entries = vec![
+            entry(1, local(0, 9, 0), Some(local(0, 12, 0))),
+            entry(2, local(0, 12, 30), Some(local(0, 17, 0))),
+        ];
+        assert!(validate(&entries).is_empty());
+    }
+
+    #[test]
+    fn reports_overlapping_interval() {
+        let entries = vec![
+            entry(2, local(0, 10, 0), Some(local(0, 11, 0))),
+            entry(1, local(0, 9, 0), Some(local(0, 10, 30))),
+        ];
+        let warnings = validate(&entries);
+
+        assert_eq!(kinds(&warnings), vec![(WarningKind::Overlap, vec![1, 2])]);
+        assert_eq!(warnings[0].from, local(0, 10, 0).to_rfc3339());
+        assert_eq!(warnings[0].to, local(0, 10, 30).to_rfc3339());
+    }
+
+    #[test]
+    fn reports_entries_crossing_midnight() {
+        let entries = vec![
+            entry(1, local(-1, 23, 0), Some(local(0, 1, 0))),
+            entry(2, local(0, 23, 30), Some(local(1, 0, 30))),
+            // Entirely on the previous day
+            entry(3, local(-1, 20, 0), Some(local(-1, 21, 0))),
+            entry(4, local(0, 9, 0), Some(local(0, 17, 0))),
+        ];
+        let warnings = validate(&entries);
+
+        assert_eq!(
+            kinds(&warnings),
+            vec![(WarningKind::SpansMidnight, vec![1]), (WarningKind::SpansMidnight, vec![2])]
+        );
+    }
+
+    #[test]
+    fn reports_gaps_longer_than_limit_within_working_hours() {
+        let entries = vec![
+            entry(1, local(0, 7, 0), Some(local(0, 8, 0))),
+            entry(2, local(0, 10, 30), Some(local(0, 11, 0))),
+            // Exactly the 60 minute limit
+            entry(3, local(0, 12, 0), Some(local(0, 13, 0))),
+            entry(4, local(0, 16, 30), Some(local(0, 17, 0))),
+        ];
+        let warnings = validate(&entries);
+
+        assert_eq!(
+            kinds(&warnings),
+            vec![(WarningKind::LargeGap, vec![1, 2]), (WarningKind::LargeGap, vec![3, 4])]
+        );
+        // The first gap only counts from the start of working hours
+        assert_eq!(warnings[0].from, local(0, 9, 0).to_rfc3339());
+        assert_eq!(warnings[0].to, local(0, 10, 30).to_rfc3339());
+    }
+
+    #[test]
+    fn contained_entry_does_not_hide_gap() {
+        let entries = vec![
+            entry(1, local(0, 9, 0), Some(local(0, 12, 0))),
+            entry(2, local(0, 10, 0), Some(local(0, 10, 30))),
+            entry(3, local(0, 14, 0), Some(local(0, 15, 0))),
+        ];
+        let warnings = validate(&entries);
+
+        assert_eq!(
+            kinds(&warnings),
+            vec![(WarningKind::Overlap, vec![1, 2]), (WarningKind::LargeGap, vec![1, 3])]
+        );
+    }
+
+    #[test]
+    fn reports_negative_and_future_entries() {
+        let entries = vec![
+            entry(1, local(0, 11, 0), Some(local(0, 10, 0))),
+            entry(2, local(0, 14, 0), Some(local(0, 18, 0))),
+        ];
+        let warnings = validate_day(&entries, day(), &ValidationSettings::default(), local(0, 15, 0));
+
+        assert_eq!(
+            kinds(&warnings),
+            vec![(WarningKind::NegativeDuration, vec![1]), (WarningKind::FutureTime, vec![2])]
+        );
+    }
+
+    fn edit(start: DateTime<Utc>, end: Option<DateTime<Utc>>) -> UpdateEntryData {
+        UpdateEntryData {
+            project_id: 1,
+            description: None,
+            started_at: format_timestamp(start),
+            stopped_at: end.map(format_timestamp),
+            is_billable: true,
+            resumed_at: None,
+        }
+    }
+
+    fn check(entries: Vec<TimeEntry>, data: &UpdateEntryData) -> Result<Vec<ValidationWarning>, String> {
+        let warnings = validate_update(entries, 1, data, &ValidationSettings::default(), local(1, 12, 0))?;
+        reject_invalid(&warnings)?;
+        Ok(warnings)
+    }
+
+    #[test]
+    fn edit_stopping_before_it_starts_is_rejected() {
+        let entries = vec![entry(1, local(0, 9, 0), Some(local(0, 10, 0)))];
+        let data = edit(local(0, 11, 0), Some(local(0, 10, 0)));
+        assert!(check(entries, &data).is_err());
+    }
+
+    #[test]
+    fn edit_ending_in_the_future_is_rejected() {
+        let entries = vec![entry(1, local(0, 9, 0), Some(local(0, 10, 0)))];
+        let data = edit(local(1, 11, 0), Some(local(1, 14, 0)));
+        assert!(check(entries, &data).is_err());
+    }
+
+    #[test]
+    fn overlapping_edit_is_allowed_with_a_warning() {
+        let entries = vec![
+            entry(1, local(0, 9, 0), Some(local(0, 10, 0))),
+            entry(2, local(0, 10, 0), Some(local(0, 11, 0))),
+        ];
+        let warnings = check(entries, &edit(local(0, 9, 0), Some(local(0, 10, 30)))).unwrap();
+        assert_eq!(kinds(&warnings), vec![(WarningKind::Overlap, vec![1, 2])]);
+    }
+
+    #[test]
+    fn edit_replaces_the_stored_entry() {
+        // The stored version overlaps entry 2; the edit doesn't
+        let entries = vec![
+            entry(1, local(0, 9, 0), Some(local(0, 10, 30))),
+            entry(2, local(0, 10, 0), Some(local(0, 11, 0))),
+        ];
+        let warnings = check(entries, &edit(local(0, 9, 0), Some(local(0, 10, 0)))).unwrap();
+        assert!(warnings.is_empty());
+    }
+}
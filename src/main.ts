@@ -1,5 +1,9 @@
 import { createApp } from 'vue'
 import './style.css'
 import App from './App.vue'
+import CommandPalette from './components/CommandPalette.vue'
 
-createApp(App).mount('#app')
+// The quick-start palette window loads index.html#palette
+const root = window.location.hash === '#palette' ? CommandPalette : App
+
+createApp(root).mount('#app')
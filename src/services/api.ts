@@ -36,6 +36,291 @@ export interface UpdateEntryData {
   is_billable: boolean
 }
 
+export interface PausedTimer {
+  entry: TimeEntry
+  elapsed_seconds: number
+  paused_at: string
+}
+
+export type ShortcutAction =
+  | 'toggle_timer'
+  | 'toggle_window'
+  | 'start_last_project'
+  | 'pause_timer'
+  | 'resume_timer'
+  | 'add_note'
+  | 'next_recent_project'
+  | { start_template: number }
+  | 'open_palette'
+
+export interface ShortcutBinding {
+  action: ShortcutAction
+  accelerator: string
+}
+
+export interface ShortcutStatus {
+  action: ShortcutAction
+  accelerator: string
+  registered: boolean
+  error: string | null
+}
+
+export type QuickStartPolicy =
+  | { type: 'last_stopped' }
+  | { type: 'pinned_project', project_id: number }
+  | { type: 'most_used_this_week' }
+
+export interface IdleSettings {
+  enabled: boolean
+  threshold_minutes: number
+}
+
+export interface IdlePrompt {
+  entry: TimeEntry
+  idle_started_at: string
+  returned_at: string
+  idle_minutes: number
+}
+
+export type IdleAction = 'keep' | 'discard' | 'split'
+
+export interface PowerSettings {
+  ask_subtract_sleep: boolean
+  min_away_minutes: number
+  include_screen_lock: boolean
+}
+
+export interface AwayPrompt {
+  entry: TimeEntry
+  away_started_at: string
+  away_ended_at: string
+  away_minutes: number
+}
+
+export type ReminderRule =
+  | { type: 'long_running', hours: number }
+  | { type: 'past_end_of_day', time: string }
+  | { type: 'no_timer_during_working_hours', start: string, end: string, weekdays_only: boolean, grace_minutes: number }
+
+export interface ReminderSettings {
+  enabled: boolean
+  repeat_minutes: number
+  rules: ReminderRule[]
+}
+
+export type NotificationCategory = 'timer' | 'reminder' | 'idle' | 'away' | 'pomodoro' | 'general'
+
+export type NotificationAction =
+  | 'stop_timer'
+  | 'start_timer'
+  | { restart_entry: number }
+  | 'snooze'
+  | 'open'
+  | 'undo'
+
+export interface NotificationSettings {
+  muted: NotificationCategory[]
+}
+
+export interface PomodoroSettings {
+  enabled: boolean
+  work_minutes: number
+  short_break_minutes: number
+  long_break_minutes: number
+  sessions_before_long_break: number
+  auto_stop_during_breaks: boolean
+}
+
+export type PomodoroPhase = 'work' | 'short_break' | 'long_break'
+
+export interface PomodoroState {
+  phase: PomodoroPhase
+  phase_started_at: string
+  phase_ends_at: string
+  completed_work_sessions: number
+  entry_id: number
+  project_id: number
+  stopped_for_break: boolean
+}
+
+export interface PomodoroSession {
+  phase: PomodoroPhase
+  project_id: number
+  started_at: string
+  ended_at: string
+  completed: boolean
+}
+
+export interface PomodoroStats {
+  today_completed: number
+  today_focus_minutes: number
+  total_completed: number
+  total_focus_minutes: number
+  interrupted: number
+  sessions: PomodoroSession[]
+}
+
+export type RoundingMode = 'nearest' | 'up' | 'down'
+
+export interface RoundingRule {
+  increment_minutes: number
+  mode: RoundingMode
+}
+
+export interface ReportSettings {
+  rounding: RoundingRule
+}
+
+export interface ReportTotals {
+  minutes: number
+  hours: number
+  billable_hours: number
+  revenue: number | null
+  entry_count: number
+}
+
+export interface ReportRow {
+  key: string
+  label: string
+  totals: ReportTotals
+}
+
+export interface Report {
+  from: string
+  to: string
+  rounding: RoundingRule
+  total: ReportTotals
+  by_day: ReportRow[]
+  by_week: ReportRow[]
+  by_client: ReportRow[]
+  by_project: ReportRow[]
+}
+
+export interface TimeEntryFilter {
+  from?: string | null
+  to?: string | null
+  client_id?: number | null
+  project_id?: number | null
+  is_billable?: boolean | null
+}
+
+export type ExportFormat = 'csv' | 'json' | 'invoice'
+
+export interface DraftEntry {
+  uid: string
+  summary: string
+  started_at: string
+  stopped_at: string
+  project_id: number | null
+  project_name: string | null
+  match_score: number
+  description: string | null
+  is_billable: boolean
+}
+
+export interface NewEntryData {
+  project_id: number
+  description: string | null
+  started_at: string
+  stopped_at: string
+  is_billable: boolean
+}
+
+export interface ValidationSettings {
+  working_start: string
+  working_end: string
+  max_gap_minutes: number
+}
+
+export type WarningKind = 'overlap' | 'negative_duration' | 'spans_midnight' | 'large_gap' | 'future_time'
+
+export interface ValidationWarning {
+  kind: WarningKind
+  entry_ids: number[]
+  from: string
+  to: string
+  message: string
+}
+
+export interface BulkChanges {
+  project_id?: number | null
+  is_billable?: boolean | null
+  description_prefix?: string | null
+}
+
+export interface BulkResult {
+  entry_id: number
+  entry: TimeEntry | null
+  error: string | null
+}
+
+export type UndoAction =
+  | { type: 'deleted'; entry: TimeEntry }
+  | { type: 'updated'; entries: TimeEntry[] }
+  | { type: 'stopped'; entry: TimeEntry }
+  | { type: 'split'; original: TimeEntry; created: TimeEntry }
+  | { type: 'merged'; survivor: TimeEntry; removed: TimeEntry[] }
+
+export interface UndoRecord {
+  id: number
+  action: UndoAction
+  label: string
+  recorded_at: string
+}
+
+export interface TimerTemplate {
+  id: number
+  name: string
+  project_id: number
+  description?: string | null
+  is_billable: boolean
+  tags: string[]
+}
+
+export interface DescriptionSuggestion {
+  project_id: number
+  description: string
+  count: number
+  last_used: string
+}
+
+export interface ProjectMatch {
+  project: Project
+  label: string
+  score: number
+}
+
+export type PaletteTarget =
+  | { type: 'project'; project_id: number }
+  | { type: 'template'; template_id: number }
+
+export interface PaletteItem {
+  target: PaletteTarget
+  label: string
+  detail?: string | null
+  score: number
+}
+
+export interface HttpApiSettings {
+  enabled: boolean
+  port: number
+  token: string
+}
+
+export interface Preferences {
+  shortcuts: ShortcutBinding[]
+  quick_start: QuickStartPolicy
+  idle: IdleSettings
+  power: PowerSettings
+  reminders: ReminderSettings
+  notifications: NotificationSettings
+  pomodoro: PomodoroSettings
+  reports: ReportSettings
+  validation: ValidationSettings
+  templates: TimerTemplate[]
+  http_api: HttpApiSettings
+}
+
 class ApiService {
   async updateTrayStatus(isRunning: boolean, projectName?: string): Promise<void> {
     try {
@@ -75,9 +360,27 @@ class ApiService {
     }
   }
 
-  async showNotification(title: string, body: string): Promise<void> {
+  async refreshTrayMenu(): Promise<void> {
     try {
-      await invoke('show_notification', { title, body })
+      await invoke('refresh_tray_menu')
+    } catch (err) {
+      console.error('Failed to refresh tray menu:', err)
+    }
+  }
+
+  async showNotification(
+    title: string,
+    body: string,
+    category?: NotificationCategory,
+    actions?: NotificationAction[]
+  ): Promise<void> {
+    try {
+      await invoke('show_notification', {
+        title,
+        body,
+        category: category || null,
+        actions: actions || null
+      })
     } catch (err) {
       console.error('Failed to show notification:', err)
     }
@@ -122,6 +425,33 @@ class ApiService {
     }
   }
 
+  async pauseTimer(): Promise<TimeEntry | null> {
+    try {
+      return await invoke<TimeEntry>('pause_timer')
+    } catch (error: any) {
+      console.error('Failed to pause timer:', error)
+      throw error
+    }
+  }
+
+  async resumeTimer(): Promise<TimeEntry | null> {
+    try {
+      return await invoke<TimeEntry>('resume_timer')
+    } catch (error: any) {
+      console.error('Failed to resume timer:', error)
+      throw error
+    }
+  }
+
+  async getPausedTimer(): Promise<PausedTimer | null> {
+    try {
+      return await invoke<PausedTimer | null>('get_paused_timer')
+    } catch (error: any) {
+      console.error('Failed to get paused timer:', error)
+      return null
+    }
+  }
+
   async getRecentProjects(): Promise<Project[]> {
     try {
       return await invoke<Project[]>('get_recent_projects')
@@ -146,6 +476,179 @@ class ApiService {
     }
   }
 
+  async getTimeEntriesRange(filter: TimeEntryFilter): Promise<TimeEntry[]> {
+    try {
+      return await invoke<TimeEntry[]>('get_time_entries_range', { filter })
+    } catch (error: any) {
+      if (error?.includes?.('Not authenticated')) {
+        throw new Error('AUTH_REQUIRED')
+      }
+      console.error('Failed to get time entries for range:', error)
+      return []
+    }
+  }
+
+  async createEntry(data: NewEntryData): Promise<TimeEntry> {
+    try {
+      return await invoke<TimeEntry>('create_entry', { data })
+    } catch (error: any) {
+      console.error('Failed to create entry:', error)
+      throw error
+    }
+  }
+
+  async splitEntry(entryId: number, at: string): Promise<TimeEntry[]> {
+    try {
+      return await invoke<TimeEntry[]>('split_entry', { entryId, at })
+    } catch (error: any) {
+      console.error('Failed to split entry:', error)
+      throw error
+    }
+  }
+
+  async mergeEntries(ids: number[]): Promise<TimeEntry> {
+    try {
+      return await invoke<TimeEntry>('merge_entries', { ids })
+    } catch (error: any) {
+      console.error('Failed to merge entries:', error)
+      throw error
+    }
+  }
+
+  async bulkUpdateEntries(ids: number[], changes: BulkChanges): Promise<BulkResult[]> {
+    try {
+      return await invoke<BulkResult[]>('bulk_update_entries', { ids, changes })
+    } catch (error: any) {
+      console.error('Failed to bulk update entries:', error)
+      throw error
+    }
+  }
+
+  async undoLastAction(): Promise<UndoRecord | null> {
+    try {
+      return await invoke<UndoRecord>('undo_last_action')
+    } catch (error: any) {
+      console.error('Failed to undo:', error)
+      return null
+    }
+  }
+
+  async getUndoStack(): Promise<UndoRecord[]> {
+    try {
+      return await invoke<UndoRecord[]>('get_undo_stack')
+    } catch (error: any) {
+      console.error('Failed to get undo stack:', error)
+      return []
+    }
+  }
+
+  async getTemplates(): Promise<TimerTemplate[]> {
+    try {
+      return await invoke<TimerTemplate[]>('get_templates')
+    } catch (error: any) {
+      console.error('Failed to get templates:', error)
+      return []
+    }
+  }
+
+  async saveTemplate(template: TimerTemplate): Promise<TimerTemplate[]> {
+    try {
+      return await invoke<TimerTemplate[]>('save_template', { template })
+    } catch (error: any) {
+      console.error('Failed to save template:', error)
+      throw error
+    }
+  }
+
+  async deleteTemplate(templateId: number): Promise<TimerTemplate[]> {
+    try {
+      return await invoke<TimerTemplate[]>('delete_template', { templateId })
+    } catch (error: any) {
+      console.error('Failed to delete template:', error)
+      throw error
+    }
+  }
+
+  async startFromTemplate(templateId: number): Promise<TimeEntry | null> {
+    try {
+      return await invoke<TimeEntry>('start_from_template', { templateId })
+    } catch (error: any) {
+      console.error('Failed to start from template:', error)
+      return null
+    }
+  }
+
+  async suggestDescriptions(projectId: number | null, query: string): Promise<DescriptionSuggestion[]> {
+    try {
+      return await invoke<DescriptionSuggestion[]>('suggest_descriptions', { projectId, query })
+    } catch (error: any) {
+      console.error('Failed to get description suggestions:', error)
+      return []
+    }
+  }
+
+  async searchProjects(query: string, limit?: number): Promise<ProjectMatch[]> {
+    try {
+      return await invoke<ProjectMatch[]>('search_projects', { query, limit })
+    } catch (error: any) {
+      console.error('Failed to search projects:', error)
+      return []
+    }
+  }
+
+  async paletteSearch(query: string): Promise<PaletteItem[]> {
+    try {
+      return await invoke<PaletteItem[]>('palette_search', { query })
+    } catch (error: any) {
+      console.error('Failed to search palette:', error)
+      return []
+    }
+  }
+
+  async paletteStart(target: PaletteTarget): Promise<TimeEntry | null> {
+    try {
+      return await invoke<TimeEntry | null>('palette_start', { target })
+    } catch (error: any) {
+      console.error('Failed to start from palette:', error)
+      throw error
+    }
+  }
+
+  async paletteStop(): Promise<TimeEntry> {
+    try {
+      return await invoke<TimeEntry>('palette_stop')
+    } catch (error: any) {
+      console.error('Failed to stop from palette:', error)
+      throw error
+    }
+  }
+
+  async hidePalette(): Promise<void> {
+    try {
+      await invoke('hide_palette')
+    } catch (error: any) {
+      console.error('Failed to hide palette:', error)
+    }
+  }
+
+  async setHttpApiSettings(settings: HttpApiSettings): Promise<HttpApiSettings> {
+    try {
+      return await invoke<HttpApiSettings>('set_http_api_settings', { settings })
+    } catch (error: any) {
+      console.error('Failed to save HTTP API settings:', error)
+      throw error
+    }
+  }
+
+  async regenerateHttpApiToken(): Promise<HttpApiSettings> {
+    try {
+      return await invoke<HttpApiSettings>('regenerate_http_api_token')
+    } catch (error: any) {
+      console.error('Failed to regenerate HTTP API token:', error)
+      throw error
+    }
+  }
+
   async restartTimer(timerId: number): Promise<TimeEntry | null> {
     try {
       return await invoke<TimeEntry>('restart_timer', { timerId })
@@ -206,6 +709,289 @@ class ApiService {
     }
   }
 
+  async checkEntryUpdate(entryId: number, data: UpdateEntryData): Promise<ValidationWarning[]> {
+    try {
+      return await invoke<ValidationWarning[]>('check_entry_update', { entryId, data })
+    } catch (error: any) {
+      console.error('Failed to check entry update:', error)
+      return []
+    }
+  }
+
+  async validateEntries(date?: string): Promise<ValidationWarning[]> {
+    try {
+      return await invoke<ValidationWarning[]>('validate_entries', { date: date || null })
+    } catch (error: any) {
+      console.error('Failed to validate entries:', error)
+      return []
+    }
+  }
+
+  async setValidationSettings(settings: ValidationSettings): Promise<ValidationSettings> {
+    try {
+      return await invoke<ValidationSettings>('set_validation_settings', { settings })
+    } catch (error: any) {
+      console.error('Failed to save validation settings:', error)
+      throw error
+    }
+  }
+
+  async getPreferences(): Promise<Preferences | null> {
+    try {
+      return await invoke<Preferences>('get_preferences')
+    } catch (error: any) {
+      console.error('Failed to get preferences:', error)
+      return null
+    }
+  }
+
+  async getShortcuts(): Promise<ShortcutStatus[]> {
+    try {
+      return await invoke<ShortcutStatus[]>('get_shortcuts')
+    } catch (error: any) {
+      console.error('Failed to get shortcuts:', error)
+      return []
+    }
+  }
+
+  async bindShortcut(action: ShortcutAction, accelerator: string): Promise<ShortcutStatus[]> {
+    try {
+      return await invoke<ShortcutStatus[]>('bind_shortcut', { action, accelerator })
+    } catch (error: any) {
+      console.error('Failed to bind shortcut:', error)
+      throw error
+    }
+  }
+
+  async unbindShortcut(accelerator: string): Promise<ShortcutStatus[]> {
+    try {
+      return await invoke<ShortcutStatus[]>('unbind_shortcut', { accelerator })
+    } catch (error: any) {
+      console.error('Failed to unbind shortcut:', error)
+      throw error
+    }
+  }
+
+  async resetShortcuts(): Promise<ShortcutStatus[]> {
+    try {
+      return await invoke<ShortcutStatus[]>('reset_shortcuts')
+    } catch (error: any) {
+      console.error('Failed to reset shortcuts:', error)
+      throw error
+    }
+  }
+
+  async setQuickStartPolicy(policy: QuickStartPolicy): Promise<QuickStartPolicy> {
+    try {
+      return await invoke<QuickStartPolicy>('set_quick_start_policy', { policy })
+    } catch (error: any) {
+      console.error('Failed to set quick-start policy:', error)
+      throw error
+    }
+  }
+
+  async getIdlePrompt(): Promise<IdlePrompt | null> {
+    try {
+      return await invoke<IdlePrompt | null>('get_idle_prompt')
+    } catch (error: any) {
+      console.error('Failed to get idle prompt:', error)
+      return null
+    }
+  }
+
+  async resolveIdle(action: IdleAction): Promise<TimeEntry | null> {
+    try {
+      return await invoke<TimeEntry | null>('resolve_idle', { action })
+    } catch (error: any) {
+      console.error('Failed to resolve idle time:', error)
+      throw error
+    }
+  }
+
+  async setIdleSettings(settings: IdleSettings): Promise<IdleSettings> {
+    try {
+      return await invoke<IdleSettings>('set_idle_settings', { settings })
+    } catch (error: any) {
+      console.error('Failed to save idle settings:', error)
+      throw error
+    }
+  }
+
+  async getAwayPrompt(): Promise<AwayPrompt | null> {
+    try {
+      return await invoke<AwayPrompt | null>('get_away_prompt')
+    } catch (error: any) {
+      console.error('Failed to get away prompt:', error)
+      return null
+    }
+  }
+
+  async resolveAway(subtract: boolean): Promise<TimeEntry | null> {
+    try {
+      return await invoke<TimeEntry | null>('resolve_away', { subtract })
+    } catch (error: any) {
+      console.error('Failed to resolve time away:', error)
+      throw error
+    }
+  }
+
+  async setPowerSettings(settings: PowerSettings): Promise<PowerSettings> {
+    try {
+      return await invoke<PowerSettings>('set_power_settings', { settings })
+    } catch (error: any) {
+      console.error('Failed to save power settings:', error)
+      throw error
+    }
+  }
+
+  async setReminderSettings(settings: ReminderSettings): Promise<ReminderSettings> {
+    try {
+      return await invoke<ReminderSettings>('set_reminder_settings', { settings })
+    } catch (error: any) {
+      console.error('Failed to save reminder settings:', error)
+      throw error
+    }
+  }
+
+  async setNotificationSettings(settings: NotificationSettings): Promise<NotificationSettings> {
+    try {
+      return await invoke<NotificationSettings>('set_notification_settings', { settings })
+    } catch (error: any) {
+      console.error('Failed to save notification settings:', error)
+      throw error
+    }
+  }
+
+  async startPomodoro(): Promise<PomodoroState> {
+    try {
+      return await invoke<PomodoroState>('start_pomodoro')
+    } catch (error: any) {
+      console.error('Failed to start pomodoro:', error)
+      throw error
+    }
+  }
+
+  async stopPomodoro(): Promise<void> {
+    try {
+      await invoke('stop_pomodoro')
+    } catch (error: any) {
+      console.error('Failed to stop pomodoro:', error)
+      throw error
+    }
+  }
+
+  async getPomodoroState(): Promise<PomodoroState | null> {
+    try {
+      return await invoke<PomodoroState | null>('get_pomodoro_state')
+    } catch (error: any) {
+      console.error('Failed to get pomodoro state:', error)
+      return null
+    }
+  }
+
+  async getPomodoroStats(): Promise<PomodoroStats> {
+    try {
+      return await invoke<PomodoroStats>('get_pomodoro_stats')
+    } catch (error: any) {
+      console.error('Failed to get pomodoro stats:', error)
+      throw error
+    }
+  }
+
+  async setPomodoroSettings(settings: PomodoroSettings): Promise<PomodoroSettings> {
+    try {
+      return await invoke<PomodoroSettings>('set_pomodoro_settings', { settings })
+    } catch (error: any) {
+      console.error('Failed to save pomodoro settings:', error)
+      throw error
+    }
+  }
+
+  async getReport(from?: string, to?: string, rounding?: RoundingRule): Promise<Report> {
+    try {
+      return await invoke<Report>('get_report', {
+        from: from || null,
+        to: to || null,
+        rounding: rounding || null
+      })
+    } catch (error: any) {
+      console.error('Failed to get report:', error)
+      throw error
+    }
+  }
+
+  async getDailySummary(date?: string): Promise<Report> {
+    try {
+      return await invoke<Report>('get_daily_summary', { date: date || null })
+    } catch (error: any) {
+      console.error('Failed to get daily summary:', error)
+      throw error
+    }
+  }
+
+  async getWeeklySummary(date?: string): Promise<Report> {
+    try {
+      return await invoke<Report>('get_weekly_summary', { date: date || null })
+    } catch (error: any) {
+      console.error('Failed to get weekly summary:', error)
+      throw error
+    }
+  }
+
+  async setReportSettings(settings: ReportSettings): Promise<ReportSettings> {
+    try {
+      return await invoke<ReportSettings>('set_report_settings', { settings })
+    } catch (error: any) {
+      console.error('Failed to save report settings:', error)
+      throw error
+    }
+  }
+
+  async exportEntries(filter: TimeEntryFilter, format: ExportFormat): Promise<string> {
+    try {
+      return await invoke<string>('export_entries', { filter, format })
+    } catch (error: any) {
+      console.error('Failed to export entries:', error)
+      throw error
+    }
+  }
+
+  async exportEntriesToFile(filter: TimeEntryFilter, format: ExportFormat): Promise<string | null> {
+    try {
+      return await invoke<string | null>('export_entries_to_file', { filter, format })
+    } catch (error: any) {
+      console.error('Failed to export entries to file:', error)
+      throw error
+    }
+  }
+
+  async exportIcs(filter: TimeEntryFilter): Promise<string | null> {
+    try {
+      return await invoke<string | null>('export_ics', { filter })
+    } catch (error: any) {
+      console.error('Failed to export calendar:', error)
+      throw error
+    }
+  }
+
+  async importIcs(path?: string): Promise<DraftEntry[]> {
+    try {
+      return await invoke<DraftEntry[]>('import_ics', { path: path || null })
+    } catch (error: any) {
+      console.error('Failed to import calendar:', error)
+      throw error
+    }
+  }
+
+  async confirmCalendarDrafts(drafts: DraftEntry[]): Promise<TimeEntry[]> {
+    try {
+      return await invoke<TimeEntry[]>('confirm_calendar_drafts', { drafts })
+    } catch (error: any) {
+      console.error('Failed to create entries from calendar:', error)
+      throw error
+    }
+  }
+
   async getSettings(): Promise<UserSettings> {
     try {
       return await invoke<UserSettings>('get_settings')